@@ -156,11 +156,15 @@ impl ToTokens for VNode {
 impl Parse for VNode {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let el = parser::parse(input)
-        .map_err(|err| {
-            syn::Error::new(
-                err.span().into(), 
-                err.message
-            )
+        .map_err(|errors| {
+            errors
+            .into_iter()
+            .map(|err| syn::Error::new(err.span().into(), err.message))
+            .reduce(|mut combined, err| {
+                combined.combine(err);
+                combined
+            })
+            .expect("the parser fails with at least one error")
         })?;
 
         Ok(el.into())
@@ -1,9 +1,7 @@
 use syn::{parse::ParseStream, Token};
 use yalp::{lexer::LexerError, symbol::Sym};
 
-use crate::html::parser::symbols::Value;
-
-use super::{ParserDef, symbols::Class};
+use super::rules::{ParserDef, Class, Value};
 
 pub type LexerDef = ParserDef;
 
@@ -66,7 +64,7 @@ impl VNodeToken {
         if input.peek(Token![<]) && input.peek2(Token![/]) {
             return Ok((
                 Class::ClosingLeftAngle, 
-                Value::ClosingLeftAngle ( input.parse()?, input.parse()?)
+                Value::ClosingLeftAngle((input.parse()?, input.parse()?))
             ));
         }
 
@@ -79,7 +77,7 @@ impl VNodeToken {
 
         if input.peek(Token![/]) && input.peek2(Token![>]) {
             return Ok(
-                (Class::SingleRightAngle, Value::SingleRightAngle(input.parse()?, input.parse()?))
+                (Class::SingleRightAngle, Value::SingleRightAngle((input.parse()?, input.parse()?)))
             );
         }
 
@@ -89,7 +87,8 @@ impl VNodeToken {
             );
         }
         
-        if let Ok(path) = input.fork().parse::<syn::Path>() {
+        if input.fork().parse::<syn::Path>().is_ok() {
+            let path: syn::Path = input.parse()?;
             if let Some(ident) = path.get_ident().cloned() {
                 return Ok(
                     (Class::Ident, Value::Ident(ident))
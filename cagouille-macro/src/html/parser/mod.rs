@@ -1,7 +1,7 @@
 use lazy_static::lazy_static;
 use syn::parse::ParseStream;
-use yalp::parser::{lr::LrParser, traits::Parser, ParserError};
-use rules::RULES;
+use yalp::parser::{lr::{compiled::LrCompiledTable, LrParser}, traits::Parser, ParserError};
+use rules::{ParserDef, RULES};
 
 use self::lexer::Lexer;
 
@@ -11,19 +11,18 @@ mod symbols;
 mod rules;
 mod lexer;
 
-pub(self) struct ParserDef;
-
-impl yalp::symbol::traits::SymbolDefinition for ParserDef {
-    type Class = symbols::Class;
-    type Value = symbols::Value;
-}
+/// Generated by `LrCompiledTable::to_rust_source`, see the `compiled_table_is_up_to_date` test.
+static TABLE: LrCompiledTable = include!("table.rs");
 
 lazy_static!{
-    pub(self) static ref PARSER: LrParser<'static, ParserDef> = LrParser::generate(&RULES);
+    pub(self) static ref PARSER: LrParser<'static, ParserDef> = LrParser::load(&RULES, &TABLE)
+        .unwrap_or_else(|err| panic!("invalid render! table: {}", err));
 }
 
 /// Parse the virtual dom renderer script
-pub fn parse<'a>(stream: ParseStream<'a>) -> Result<VElementNode, ParserError> {
+/// 
+/// Returns every syntax error found in the script.
+pub fn parse<'a>(stream: ParseStream<'a>) -> Result<VElementNode, Vec<ParserError>> {
     let lexer = Lexer::new(stream);
     let el: VElementNode = PARSER.parse(lexer)?;
     Ok(el)
@@ -31,9 +30,87 @@ pub fn parse<'a>(stream: ParseStream<'a>) -> Result<VElementNode, ParserError> {
 
 #[cfg(test)]
 mod tests {
+    use quote::quote;
+    use syn::parse::Parser;
+    use yalp::parser::{lr::LrParser, traits::Parser as _, ParserErrorKind};
+
+    use crate::html::{VChildrenNode, VElementAttribute};
+    use super::Lexer;
+
     #[test]
     fn check_parser() {
         let table = &super::PARSER.table;
-        println!("{:?}", table);
+        println!("{}", table.to_text(&super::RULES));
+    }
+
+    #[test]
+    fn compiled_table_is_up_to_date() {
+        let compiled = LrParser::generate(&super::RULES).unwrap().compile();
+        assert_eq!(compiled, super::TABLE, "generated table:\n{}", compiled.to_rust_source());
+        assert_eq!(compiled.to_rust_source(), include_str!("table.rs").trim_end());
+    }
+
+    #[test]
+    fn parse_nested_elements() {
+        let el = (|input: syn::parse::ParseStream| super::parse(input).map_err(|mut errors| errors.remove(0).into_syn_error()))
+        .parse2(quote! {
+            <div class="foo">
+                <span/>
+                "bar"
+                <p></p>
+            </div>
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+
+        assert_eq!(el.tag.unwrap().to_string(), "div");
+        assert_eq!(el.attrs.0.len(), 1);
+        assert_eq!(el.children.0.len(), 3);
+    }
+
+    #[test]
+    fn report_every_syntax_error() {
+        let errors = (|input: syn::parse::ParseStream| Ok(super::parse(input)))
+        .parse2(quote! {
+            <div>
+                <p = ></p>
+                <span></span>
+                <b = ></b>
+            </div>
+        })
+        .unwrap()
+        .err()
+        .unwrap();
+
+        assert_eq!(errors.len(), 2);
+        assert!(
+            matches!(&errors[0].kind, ParserErrorKind::UnexpectedToken(token) if token.found == "`=`"),
+            "{}", errors[0].message
+        );
+    }
+
+    #[test]
+    fn parse_fragment_entry() {
+        let children: VChildrenNode = (|input: syn::parse::ParseStream| super::PARSER
+            .parse_entry("fragment", Lexer::new(input))
+            .map_err(|mut errors| errors.remove(0).into_syn_error()))
+        .parse2(quote! {
+            <span/>
+            "bar"
+            <p></p>
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+
+        assert_eq!(children.0.len(), 3);
+    }
+
+    #[test]
+    fn parse_attribute_entry() {
+        let attr: VElementAttribute = (|input: syn::parse::ParseStream| super::PARSER
+            .parse_entry("attribute", Lexer::new(input))
+            .map_err(|mut errors| errors.remove(0).into_syn_error()))
+        .parse2(quote! { class="foo" })
+        .unwrap_or_else(|err| panic!("{}", err));
+
+        assert_eq!(attr.name.to_string(), "class");
     }
-}
\ No newline at end of file
+}
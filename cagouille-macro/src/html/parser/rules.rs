@@ -1,188 +1,125 @@
-use lazy_static::lazy_static;
+use crate::html::{VElementNode, VNode, VChildrenNode, VElementAttributes, VElementAttribute};
 
-use yalp::parser::rule::ParserRuleSet;
-use yalp::symbol::traits::Symbol;
+use super::symbols::{OpenTag, SingleTag, CloseTag};
 
-use crate::html::{VElementNode, VNode, VChildrenNode, VElementAttributes, VElementAttribute};
+/*  Current grammar rules (v0.1):
 
-use super::symbols::{Class, OpenTag, SingleTag, CloseTag};
-use super::ParserDef;
-
-lazy_static!{
-    /*  Current grammar rules (v0.1):
-
-        Root -> Element
-        Node -> Element
-        Node -> block
-        Node -> lit
-        Element -> SingleTag
-        Element -> OpenTag Children CloseTag
-        Element -> OpenTag CloseTag
-        OpenTag -> < ident >
-        OpenTag -> < ident Attrs >
-        CloseTag -> </ ident >
-        SingleTag -> < ident Attrs />
-        SingleTag -> < ident />
-        Children -> Children Node
-        Children -> Node
-        Attrs -> Attrs Attr
-        Attrs -> Attr
-        Attr -> ident = block
-        Attr -> ident = lit
-    */
+    Root -> Element
+    Fragment -> Node*
+    Node -> Element
+    Node -> block
+    Node -> lit
+    Element -> SingleTag
+    Element -> OpenTag Node* CloseTag
+    OpenTag -> < ident Attr* >
+    OpenTag -> < ident error >
+    CloseTag -> </ ident >
+    SingleTag -> < ident Attr* />
+    SingleTag -> < ident error />
+    Attr -> ident = block
+    Attr -> ident = lit
+
+    Entry points: `fragment` from Fragment, `attribute` from Attr
+*/
+yalp::grammar! {
+    /// The symbol class
+    pub(super) symbols ParserDef { class: Class, value: Value }
 
     /// Virtual DOM's renderer grammar rules
-    pub(super) static ref RULES: ParserRuleSet<ParserDef> = ParserRuleSet::new()
-    .add( // Root -> Element
-        Class::Root, [Class::Element],
-        &|mut syms| {
-            Ok(syms.remove(0).value)
-        }
-    )
-    .add( // Node -> Element
-        Class::Node, [Class::Element],
-        &|mut syms| {
-            let el: VElementNode = syms.remove(0).into_value()?;
-            Ok(VNode::Element(el).into())
-        } 
-    )
-    .add( // Node -> block
-        Class::Node, [Class::Block],
-        &|mut syms| {
-            let bck = syms.remove(0).into_value()?;
-            Ok(VNode::Block(bck).into())
-        }
-    )
-    .add( // Node -> lit 
-        Class::Node, [Class::Lit],
-        &|mut syms| {
-            let lit = syms.remove(0).into_value()?;
-            Ok(VNode::Lit(lit).into())
-        }       
-    )
-    .add( // Element -> SingleTag 
-        Class::Element, [Class::SingleTag],
-        &|mut syms| {
-            let SingleTag{tag, attrs} = syms.remove(0).into_value()?;
-            
-            Ok(VElementNode {
-                tag,
-                attrs,
-                children: VChildrenNode::default()
-            }.into())
-        }
-    )
-    .add( // Element -> OpenTag Children CloseTag
-        Class::Element, [Class::OpenTag, Class::ElementChildren, Class::CloseTag],
-        &|mut syms| {
-            let OpenTag { tag, attrs } = syms.remove(0).into_value()?;
-            let children: VChildrenNode = syms.remove(0).into_value()?;
-            let CloseTag {tag: _} = syms.remove(0).into_value()?;
-            
-            Ok(VElementNode {
-                tag,
-                attrs,
-                children
-            }.into())
-        }
-    )
-    .add( // Element -> OpenTag CloseTag
-        Class::Element, [Class::OpenTag, Class::CloseTag],
-        &|mut syms| {
-            let OpenTag { tag, attrs } = syms.remove(0).into_value()?;
-            let CloseTag {tag: _} = syms.remove(0).into_value()?;
-            Ok(VElementNode {
-                tag,
-                attrs,
-                children: VChildrenNode::default()
-            }.into())
-        }
-    )
-    .add( // OpenTag -> < ident >
-        Class::OpenTag, [Class::LeftAngle, Class::Ident, Class::RightAngle],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(1).into_value()?;
-            Ok(OpenTag{ tag: Some(ident), attrs: VElementAttributes::default()}.into())
-        }
-    )
-    .add( // OpenTag -> < ident Attrs >
-        Class::OpenTag, [Class::LeftAngle, Class::Ident, Class::ElementAttributes, Class::RightAngle],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(1).into_value()?;
-            let attrs: VElementAttributes = syms.remove(1).into_value()?;
-            Ok(OpenTag{ tag: Some(ident), attrs}.into())
-        }
-    )
-    .add( // CloseTag -> </ ident >
-        Class::CloseTag, [Class::ClosingLeftAngle, Class::Ident, Class::RightAngle],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(1).into_value()?;
-            Ok(CloseTag{tag: Some(ident)}.into())
-        }
-
-    )
-    .add( // SingleTag -> < ident Attrs />
-        Class::SingleTag, [Class::LeftAngle, Class::Ident, Class::ElementAttributes, Class::SingleRightAngle],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(1).into_value()?;
-            let attrs: VElementAttributes = syms.remove(1).into_value()?;
-            Ok(SingleTag{tag: Some(ident), attrs}.into())
-        }   
-    )
-    .add( // SingleTag -> < ident />
-        Class::SingleTag, [Class::LeftAngle, Class::Ident, Class::SingleRightAngle],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(1).into_value()?;
-            Ok(SingleTag{tag: Some(ident), attrs: VElementAttributes::default()}.into())
-        }   
-    )
-    .add( // Children -> Children Node
-        Class::ElementChildren, [Class::ElementChildren, Class::Node],
-        &|mut syms| {
-            let mut children: VChildrenNode = syms.remove(0).into_value()?;
-            let node: VNode = syms.remove(0).into_value()?;
-            children.push(node);
-            Ok(children.into())
-        }
-    )
-    .add( // Children -> Node
-        Class::ElementChildren, [Class::Node],
-        &|mut syms| {
-            let node: VNode = syms.remove(0).into_value()?;
-            Ok(VChildrenNode::from_iter([node]).into())
-        }
-    )
-    .add( // Attrs -> Attrs Attr
-        Class::ElementAttributes, [Class::ElementAttributes, Class::ElementAttribute],
-        &|mut syms| {
-            let mut attrs: VElementAttributes = syms.remove(0).into_value()?;
-            let attr: VElementAttribute = syms.remove(0).into_value()?;
-            attrs.push(attr);
-            Ok(attrs.into())
-        }
-    )
-    .add( // Attrs -> Attr
-        Class::ElementAttributes, [Class::ElementAttribute],
-        &|mut syms| {
-            let attr: VElementAttribute = syms.remove(0).into_value()?;
-            Ok(VElementAttributes::from_iter([attr]).into())
-        }
-    )
-    .add( // Attr -> ident = block
-        Class::ElementAttribute, [Class::Ident, Class::Equal, Class::Block],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(0).into_value()?;
-            let block: syn::Block = syms.remove(1).into_value()?;
-            Ok(VElementAttribute::new(ident, block).into())
-        }
-    )
-    .add( // Attr -> ident = lit
-        Class::ElementAttribute, [Class::Ident, Class::Equal, Class::Block],
-        &|mut syms| {
-            let ident: syn::Ident = syms.remove(0).into_value()?;
-            let lit: syn::Lit = syms.remove(1).into_value()?;
-            Ok(VElementAttribute::new(ident, lit).into())
-        }
-    )
-    .to_owned();
-}
\ No newline at end of file
+    pub(super) rules RULES;
+
+    terminals {
+        /// $
+        EOS = "end of input",
+        /// <
+        LeftAngle: syn::Token![<] = "`<`",
+        /// </
+        ClosingLeftAngle: (syn::Token![<], syn::Token![/]) = "`</`",
+        /// />
+        SingleRightAngle: (syn::Token![/], syn::Token![>]) = "`/>`",
+        /// >
+        RightAngle: syn::Token![>] = "`>`",
+        /// A path at which a named item is exported
+        /// Exemple: std::collections::HashMap
+        Path: syn::Path = "path",
+        /// A literal, a number or a str.
+        Lit: syn::Lit = "literal",
+        /// An identifier
+        Ident: syn::Ident = "identifier",
+        /// =
+        Equal: syn::Token![=] = "`=`",
+        /// A braced block containing Rust statements.
+        Block: syn::Block = "block",
+        /// A syntax error, never produced by the lexer.
+        SyntaxError
+    }
+
+    nonterminals {
+        /// Root node
+        Root: VElementNode,
+        /// A list of nodes, the start of the `fragment` entry point
+        Fragment: VChildrenNode,
+        /// A vdom renderer node
+        Node: VNode,
+        /// An element renderer
+        ///
+        /// Either an html element renderer or a component renderer
+        ///
+        /// Exemple:
+        /// - <div class="blabla"></div>
+        /// - <MyFoo prop_attr={bar}></MyFoo>
+        Element: VElementNode,
+        /// An open tag
+        ///
+        /// Exemple:
+        /// - <tag-name attr_1="blabla">
+        OpenTag: OpenTag,
+        /// A close tag
+        ///
+        /// Exemple:
+        /// - </tag-name>
+        CloseTag: CloseTag,
+        /// A single tag
+        ///
+        /// Exemple:
+        /// - <tag-name attr_1="blabla"/>
+        SingleTag: SingleTag,
+        /// An element attribute, the start of the `attribute` entry point
+        ElementAttribute: VElementAttribute
+    }
+
+    eos EOS;
+    error SyntaxError;
+
+    entry fragment Fragment;
+    entry attribute ElementAttribute;
+
+    Root -> Element => |el| Ok(el);
+    Fragment -> Node* => |nodes| Ok(VChildrenNode::from_iter(nodes));
+
+    Node -> Element => |el| Ok(VNode::Element(el));
+    Node -> Block => |bck| Ok(VNode::Block(bck));
+    Node -> Lit => |lit| Ok(VNode::Lit(lit));
+
+    Element -> SingleTag => |SingleTag { tag, attrs }| Ok(VElementNode {
+        tag,
+        attrs,
+        children: VChildrenNode::default()
+    });
+    Element -> OpenTag Node* CloseTag => |OpenTag { tag, attrs }, children, _| Ok(VElementNode {
+        tag,
+        attrs,
+        children: VChildrenNode::from_iter(children)
+    });
+
+    OpenTag -> LeftAngle Ident ElementAttribute* RightAngle => |_, ident, attrs, _| Ok(OpenTag { tag: Some(ident), attrs: VElementAttributes::from_iter(attrs) });
+    OpenTag -> LeftAngle Ident SyntaxError RightAngle => |_, ident, _, _| Ok(OpenTag { tag: Some(ident), attrs: VElementAttributes::default() });
+
+    CloseTag -> ClosingLeftAngle Ident RightAngle => |_, ident, _| Ok(CloseTag { tag: Some(ident) });
+
+    SingleTag -> LeftAngle Ident ElementAttribute* SingleRightAngle => |_, ident, attrs, _| Ok(SingleTag { tag: Some(ident), attrs: VElementAttributes::from_iter(attrs) });
+    SingleTag -> LeftAngle Ident SyntaxError SingleRightAngle => |_, ident, _, _| Ok(SingleTag { tag: Some(ident), attrs: VElementAttributes::default() });
+
+    ElementAttribute -> Ident Equal Block => |ident, _, block| Ok(VElementAttribute::new(ident, block));
+    ElementAttribute -> Ident Equal Lit => |ident, _, lit| Ok(VElementAttribute::new(ident, lit));
+}
@@ -0,0 +1,129 @@
+::yalp::parser::lr::compiled::LrCompiledTable {
+    fingerprint: 0xb23ea0a04b1022e4,
+    states: ::std::borrow::Cow::Borrowed(&[
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(12, ::yalp::parser::lr::action::LrParserOp::Shift(6))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(3, 3), (9, 4), (10, 5)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(14))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(4, 7), (5, 8)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(13, ::yalp::parser::lr::action::LrParserOp::Shift(10))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(18, 9)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Accept)]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(5)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(5)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(5)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(5)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(5))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(16, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(14)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(14))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(5, 11)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(13, ::yalp::parser::lr::action::LrParserOp::Shift(12))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Accept)]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(7, ::yalp::parser::lr::action::LrParserOp::Shift(15)), (8, ::yalp::parser::lr::action::LrParserOp::Shift(16)), (12, ::yalp::parser::lr::action::LrParserOp::Shift(6)), (0, ::yalp::parser::lr::action::LrParserOp::Reduce(1))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(6, 13), (3, 14), (9, 4), (10, 5)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Accept)]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(19, ::yalp::parser::lr::action::LrParserOp::Shift(17))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(16, ::yalp::parser::lr::action::LrParserOp::Shift(19)), (7, ::yalp::parser::lr::action::LrParserOp::Shift(15)), (8, ::yalp::parser::lr::action::LrParserOp::Shift(16)), (12, ::yalp::parser::lr::action::LrParserOp::Shift(6))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(11, 18), (6, 13), (3, 14), (9, 4), (10, 5)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(1, ::yalp::parser::lr::action::LrParserOp::Shift(21)), (17, ::yalp::parser::lr::action::LrParserOp::Reduce(16)), (15, ::yalp::parser::lr::action::LrParserOp::Reduce(16)), (13, ::yalp::parser::lr::action::LrParserOp::Reduce(16))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(14, 20)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(15)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(15)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(15)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(15)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(15))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(2)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(2)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(2)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(2)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(2))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(3)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(3)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(3)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(3)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(3))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(4)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(4)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(4)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(4)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(4))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(7, ::yalp::parser::lr::action::LrParserOp::Shift(22)), (8, ::yalp::parser::lr::action::LrParserOp::Shift(23))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(6)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(6)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(6)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(6)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(6))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(13, ::yalp::parser::lr::action::LrParserOp::Shift(24))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(17, ::yalp::parser::lr::action::LrParserOp::Shift(25)), (15, ::yalp::parser::lr::action::LrParserOp::Shift(26)), (13, ::yalp::parser::lr::action::LrParserOp::Shift(10))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(18, 27)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(17, ::yalp::parser::lr::action::LrParserOp::Shift(28)), (15, ::yalp::parser::lr::action::LrParserOp::Shift(29))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(12)), (17, ::yalp::parser::lr::action::LrParserOp::Reduce(12)), (15, ::yalp::parser::lr::action::LrParserOp::Reduce(12)), (13, ::yalp::parser::lr::action::LrParserOp::Reduce(12))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(13)), (17, ::yalp::parser::lr::action::LrParserOp::Reduce(13)), (15, ::yalp::parser::lr::action::LrParserOp::Reduce(13)), (13, ::yalp::parser::lr::action::LrParserOp::Reduce(13))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(15, ::yalp::parser::lr::action::LrParserOp::Shift(30))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(10)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(10)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(10)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(10)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(10))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(7, ::yalp::parser::lr::action::LrParserOp::Reduce(7)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(7)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(7)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(7))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(17, ::yalp::parser::lr::action::LrParserOp::Reduce(17)), (15, ::yalp::parser::lr::action::LrParserOp::Reduce(17)), (13, ::yalp::parser::lr::action::LrParserOp::Reduce(17))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(11)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(11)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(11)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(11)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(11))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(7, ::yalp::parser::lr::action::LrParserOp::Reduce(8)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(8)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(8)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(8))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(9)), (7, ::yalp::parser::lr::action::LrParserOp::Reduce(9)), (8, ::yalp::parser::lr::action::LrParserOp::Reduce(9)), (12, ::yalp::parser::lr::action::LrParserOp::Reduce(9)), (16, ::yalp::parser::lr::action::LrParserOp::Reduce(9))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+    ])
+}
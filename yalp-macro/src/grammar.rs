@@ -0,0 +1,826 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Expr, ExprClosure, Ident, LitStr, Pat, Token, Type, Visibility,
+};
+
+mod kw {
+    syn::custom_keyword!(symbols);
+    syn::custom_keyword!(rules);
+    syn::custom_keyword!(terminals);
+    syn::custom_keyword!(nonterminals);
+    syn::custom_keyword!(eos);
+    syn::custom_keyword!(error);
+    syn::custom_keyword!(left);
+    syn::custom_keyword!(right);
+    syn::custom_keyword!(nonassoc);
+    syn::custom_keyword!(class);
+    syn::custom_keyword!(value);
+    syn::custom_keyword!(prec);
+    syn::custom_keyword!(entry);
+    syn::custom_keyword!(context);
+}
+
+/// A terminal or non-terminal declaration
+///
+/// Exemple:
+/// - Ident: syn::Ident
+/// - EOS
+/// - RightAngle: syn::Token![>] = "`>`"
+struct SymbolDecl {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    ty: Option<Type>,
+    /// The name of the symbol in error messages
+    display_name: Option<LitStr>,
+    terminal: bool,
+    /// Declared by the desugaring of EBNF items
+    helper: bool,
+}
+
+impl SymbolDecl {
+    fn parse_with(input: ParseStream, terminal: bool) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name: Ident = input.parse()?;
+
+        let ty = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let display_name = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { attrs, name, ty, display_name, terminal, helper: false })
+    }
+
+    fn parse_terminal(input: ParseStream) -> syn::Result<Self> {
+        Self::parse_with(input, true)
+    }
+
+    fn parse_nonterminal(input: ParseStream) -> syn::Result<Self> {
+        Self::parse_with(input, false)
+    }
+
+    /// The type of the symbol's value, unit if none was declared.
+    fn value_type(&self) -> TokenStream {
+        match &self.ty {
+            Some(ty) => ty.to_token_stream(),
+            None => quote! { () },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Associativity {
+    Left,
+    Right,
+    NonAssoc,
+}
+
+/// A grammar rule
+///
+/// Exemple:
+/// - Element -> OpenTag Children CloseTag => |open, children, close| { ... };
+/// - Expr -> Minus Expr %prec UMinus => |_, e| Ok(-e);
+/// - OpenTag -> LeftAngle Ident Attr* RightAngle => |_, tag, attrs, _| { ... };
+/// - Empty -> => || Ok(());
+struct RuleDecl {
+    lhs: Ident,
+    rhs: Vec<RhsItem>,
+    prec: Option<Ident>,
+    reducer: ExprClosure,
+}
+
+impl Parse for RuleDecl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lhs: Ident = input.parse()?;
+        input.parse::<Token![->]>()?;
+
+        let rhs = RhsItem::parse_sequence(input)?;
+
+        let prec = if input.peek(Token![%]) && input.peek2(kw::prec) {
+            input.parse::<Token![%]>()?;
+            input.parse::<kw::prec>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        input.parse::<Token![=>]>()?;
+
+        let reducer = match input.parse::<Expr>()? {
+            Expr::Closure(closure) => closure,
+            expr => return Err(syn::Error::new_spanned(expr, "expecting a closure reducing the symbols of the rule")),
+        };
+
+        input.parse::<Token![;]>()?;
+
+        Ok(Self { lhs, rhs, prec, reducer })
+    }
+}
+
+impl RuleDecl {
+    /// The symbols of the rule, once desugared.
+    fn symbols(&self) -> Vec<&Ident> {
+        self.rhs
+        .iter()
+        .map(|item| match item {
+            RhsItem::Symbol(sym) => sym,
+            _ => unreachable!("the rule is not desugared"),
+        })
+        .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Repetition {
+    /// X?, produces an Option
+    Optional,
+    /// X*, produces a Vec
+    Star,
+    /// X+, produces a non-empty Vec
+    Plus,
+}
+
+/// An element of the right-hand side of a rule, EBNF operators are desugared into helper non-terminals.
+enum RhsItem {
+    Symbol(Ident),
+    /// (A B), produces a tuple of the values, or the value itself if there is a single element.
+    Group(Vec<RhsItem>, Span),
+    Repeat(Box<RhsItem>, Repetition),
+}
+
+impl RhsItem {
+    fn parse_sequence(input: ParseStream) -> syn::Result<Vec<Self>> {
+        let mut items = vec![];
+
+        while input.peek(Ident) || input.peek(syn::token::Paren) {
+            let mut item = if input.peek(syn::token::Paren) {
+                let content;
+                let paren = syn::parenthesized!(content in input);
+                let items = Self::parse_sequence(&content)?;
+
+                if !content.is_empty() {
+                    return Err(content.error("expecting a symbol"));
+                }
+
+                if items.is_empty() {
+                    return Err(syn::Error::new(paren.span.join(), "empty group"));
+                }
+
+                Self::Group(items, paren.span.join())
+            } else {
+                Self::Symbol(input.parse()?)
+            };
+
+            loop {
+                let repetition = if input.peek(Token![?]) {
+                    input.parse::<Token![?]>()?;
+                    Repetition::Optional
+                } else if input.peek(Token![*]) {
+                    input.parse::<Token![*]>()?;
+                    Repetition::Star
+                } else if input.peek(Token![+]) {
+                    input.parse::<Token![+]>()?;
+                    Repetition::Plus
+                } else {
+                    break;
+                };
+
+                item = Self::Repeat(Box::new(item), repetition);
+            }
+
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    /// Iterate over the symbols the item refers to.
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Ident) -> syn::Result<()>) -> syn::Result<()> {
+        match self {
+            Self::Symbol(sym) => f(sym),
+            Self::Group(items, _) => items.iter().try_for_each(|item| item.visit(f)),
+            Self::Repeat(item, _) => item.visit(f),
+        }
+    }
+
+    /// A textual form of the item, used to share the helper non-terminals between identical items.
+    fn key(&self) -> String {
+        match self {
+            Self::Symbol(sym) => sym.to_string(),
+            Self::Group(items, _) => format!("({})", items.iter().map(Self::key).collect::<Vec<_>>().join(" ")),
+            Self::Repeat(item, Repetition::Optional) => format!("{}?", item.key()),
+            Self::Repeat(item, Repetition::Star) => format!("{}*", item.key()),
+            Self::Repeat(item, Repetition::Plus) => format!("{}+", item.key()),
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Self::Symbol(sym) => sym.span(),
+            Self::Group(_, span) => *span,
+            Self::Repeat(item, _) => item.span(),
+        }
+    }
+}
+
+pub struct Grammar {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    def: Option<Ident>,
+    class: Option<Ident>,
+    value: Option<Ident>,
+    rules_attrs: Vec<Attribute>,
+    rules_vis: Visibility,
+    rules_name: Option<Ident>,
+    /// The type of the context passed to the reducers, `()` if not declared
+    context: Option<Type>,
+    symbols: Vec<SymbolDecl>,
+    eos: Option<Ident>,
+    error: Option<Ident>,
+    precedences: Vec<(Associativity, Vec<Ident>)>,
+    /// The entry points, and the non-terminal each one starts from
+    entries: Vec<(Ident, Ident)>,
+    rules: Vec<RuleDecl>,
+}
+
+impl Parse for Grammar {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut grammar = Self {
+            attrs: vec![],
+            vis: Visibility::Inherited,
+            def: None,
+            class: None,
+            value: None,
+            rules_attrs: vec![],
+            rules_vis: Visibility::Inherited,
+            rules_name: None,
+            context: None,
+            symbols: vec![],
+            eos: None,
+            error: None,
+            precedences: vec![],
+            entries: vec![],
+            rules: vec![],
+        };
+
+        while !input.is_empty() {
+            // Lhs -> ...
+            if input.peek(Ident) && input.peek2(Token![->]) {
+                grammar.rules.push(input.parse()?);
+                continue;
+            }
+
+            let attrs = input.call(Attribute::parse_outer)?;
+            let vis: Visibility = input.parse()?;
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::symbols) {
+                // symbols Def { class: Class, value: Value }
+                input.parse::<kw::symbols>()?;
+                grammar.attrs = attrs;
+                grammar.vis = vis;
+                grammar.def = Some(input.parse()?);
+
+                let content;
+                braced!(content in input);
+                content.parse::<kw::class>()?;
+                content.parse::<Token![:]>()?;
+                grammar.class = Some(content.parse()?);
+                content.parse::<Token![,]>()?;
+                content.parse::<kw::value>()?;
+                content.parse::<Token![:]>()?;
+                grammar.value = Some(content.parse()?);
+                content.parse::<Option<Token![,]>>()?;
+            } else if lookahead.peek(kw::rules) {
+                // rules RULES;
+                input.parse::<kw::rules>()?;
+                grammar.rules_attrs = attrs;
+                grammar.rules_vis = vis;
+                grammar.rules_name = Some(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::terminals) || lookahead.peek(kw::nonterminals) {
+                // terminals { A, B: Type }
+                let terminal = input.peek(kw::terminals);
+                if terminal {
+                    input.parse::<kw::terminals>()?;
+                } else {
+                    input.parse::<kw::nonterminals>()?;
+                }
+
+                let content;
+                braced!(content in input);
+                let parser = if terminal { SymbolDecl::parse_terminal } else { SymbolDecl::parse_nonterminal };
+                let decls = Punctuated::<SymbolDecl, Token![,]>::parse_terminated_with(&content, parser)?;
+                grammar.symbols.extend(decls);
+            } else if lookahead.peek(kw::context) {
+                // context Interner;
+                input.parse::<kw::context>()?;
+                grammar.context = Some(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::eos) {
+                // eos EOS;
+                input.parse::<kw::eos>()?;
+                grammar.eos = Some(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::error) {
+                // error SyntaxError;
+                input.parse::<kw::error>()?;
+                grammar.error = Some(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::entry) {
+                // entry fragment Fragment;
+                input.parse::<kw::entry>()?;
+                let name = input.parse()?;
+                let lhs = input.parse()?;
+                input.parse::<Token![;]>()?;
+
+                grammar.entries.push((name, lhs));
+            } else if lookahead.peek(kw::left) || lookahead.peek(kw::right) || lookahead.peek(kw::nonassoc) {
+                // left A B;
+                let assoc = if input.peek(kw::left) {
+                    input.parse::<kw::left>()?;
+                    Associativity::Left
+                } else if input.peek(kw::right) {
+                    input.parse::<kw::right>()?;
+                    Associativity::Right
+                } else {
+                    input.parse::<kw::nonassoc>()?;
+                    Associativity::NonAssoc
+                };
+
+                let mut terminals = vec![];
+                while input.peek(Ident) {
+                    terminals.push(input.parse()?);
+                }
+                input.parse::<Token![;]>()?;
+
+                grammar.precedences.push((assoc, terminals));
+            } else {
+                return Err(lookahead.error());
+            }
+        }
+
+        Ok(grammar)
+    }
+}
+
+impl Grammar {
+    fn symbol(&self, name: &Ident) -> syn::Result<&SymbolDecl> {
+        self.symbols
+        .iter()
+        .find(|sym| sym.name == *name)
+        .ok_or_else(|| syn::Error::new(name.span(), format!("undeclared symbol `{}`", name)))
+    }
+
+    fn terminal(&self, name: &Ident) -> syn::Result<&SymbolDecl> {
+        let sym = self.symbol(name)?;
+
+        if !sym.terminal {
+            return Err(syn::Error::new(name.span(), format!("`{}` is not a terminal", name)));
+        }
+
+        Ok(sym)
+    }
+
+    /// Check the declarations are complete and consistent.
+    fn validate(&self) -> syn::Result<()> {
+        let missing = |what: &str| syn::Error::new(Span::call_site(), format!("missing `{}` declaration", what));
+
+        self.def.as_ref().ok_or_else(|| missing("symbols"))?;
+        self.rules_name.as_ref().ok_or_else(|| missing("rules"))?;
+        self.terminal(self.eos.as_ref().ok_or_else(|| missing("eos"))?)?;
+
+        if let Some(error) = &self.error {
+            self.terminal(error)?;
+        }
+
+        for (_, terminals) in self.precedences.iter() {
+            for term in terminals {
+                self.terminal(term)?;
+            }
+        }
+
+        for sym in self.symbols.iter() {
+            if !sym.terminal && sym.ty.is_none() {
+                return Err(syn::Error::new(sym.name.span(), format!("the non-terminal `{}` must declare the type of its value", sym.name)));
+            }
+        }
+
+        if self.rules.is_empty() {
+            return Err(syn::Error::new(Span::call_site(), "the grammar has no rule"));
+        }
+
+        for rule in self.rules.iter() {
+            if self.symbol(&rule.lhs)?.terminal {
+                return Err(syn::Error::new(rule.lhs.span(), format!("the terminal `{}` cannot be reduced", rule.lhs)));
+            }
+
+            for item in rule.rhs.iter() {
+                item.visit(&mut |sym| self.symbol(sym).map(|_| ()))?;
+            }
+
+            if let Some(prec) = &rule.prec {
+                self.terminal(prec)?;
+            }
+
+            // The context is the optional last argument.
+            if rule.reducer.inputs.len() != rule.rhs.len() && rule.reducer.inputs.len() != rule.rhs.len() + 1 {
+                return Err(syn::Error::new_spanned(
+                    &rule.reducer.inputs,
+                    format!("the reducer expects {} argument(s), one for each symbol of the rule, and optionally the context", rule.rhs.len()),
+                ));
+            }
+        }
+
+        for (name, lhs) in self.entries.iter() {
+            if self.entries.iter().filter(|(other, _)| other == name).count() > 1 {
+                return Err(syn::Error::new(name.span(), format!("the entry point `{}` is declared more than once", name)));
+            }
+
+            if self.symbol(lhs)?.terminal {
+                return Err(syn::Error::new(lhs.span(), format!("the entry point `{}` must start from a non-terminal", name)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the EBNF items of the rules by helper non-terminals, and their rules.
+    fn desugar(&mut self) -> syn::Result<()> {
+        let mut helpers: Vec<(String, Ident)> = vec![];
+        let mut helper_rules = vec![];
+
+        for id in 0..self.rules.len() {
+            let rhs = std::mem::take(&mut self.rules[id].rhs);
+            self.rules[id].rhs = rhs
+            .into_iter()
+            .map(|item| self.desugar_item(item, &mut helpers, &mut helper_rules).map(RhsItem::Symbol))
+            .collect::<syn::Result<_>>()?;
+        }
+
+        self.rules.extend(helper_rules);
+        Ok(())
+    }
+
+    /// Returns the symbol standing for the item, declaring the helper non-terminal if needed.
+    fn desugar_item(&mut self, item: RhsItem, helpers: &mut Vec<(String, Ident)>, rules: &mut Vec<RuleDecl>) -> syn::Result<Ident> {
+        if let RhsItem::Symbol(sym) = item {
+            return Ok(sym);
+        }
+
+        let key = item.key();
+        if let Some((_, helper)) = helpers.iter().find(|(k, _)| *k == key) {
+            return Ok(Ident::new(&helper.to_string(), item.span()));
+        }
+
+        let span = item.span();
+        let closure = |tokens: TokenStream| syn::parse2::<ExprClosure>(tokens);
+
+        let (name, ty, productions) = match item {
+            RhsItem::Symbol(_) => unreachable!(),
+            RhsItem::Group(items, _) => {
+                let syms = items
+                .into_iter()
+                .map(|item| self.desugar_item(item, helpers, rules))
+                .collect::<syn::Result<Vec<_>>>()?;
+
+                let types = syms.iter().map(|sym| self.symbol(sym).map(SymbolDecl::value_type)).collect::<syn::Result<Vec<_>>>()?;
+                let values = (0..syms.len()).map(|i| format_ident!("v{}", i)).collect::<Vec<_>>();
+
+                let (ty, reducer) = if syms.len() == 1 {
+                    (quote! { #(#types)* }, closure(quote! { |v0| Ok(v0) })?)
+                } else {
+                    (quote! { (#(#types),*) }, closure(quote! { |#(#values),*| Ok((#(#values),*)) })?)
+                };
+
+                let name = format_ident!("__Group{}", helpers.len(), span = span);
+                (name, ty, vec![(syms.into_iter().map(RhsItem::Symbol).collect::<Vec<_>>(), reducer)])
+            }
+            RhsItem::Repeat(item, repetition) => {
+                let sym = self.desugar_item(*item, helpers, rules)?;
+                let inner = self.symbol(&sym)?.value_type();
+
+                match repetition {
+                    Repetition::Optional => {
+                        let name = format_ident!("__{}Optional", sym, span = span);
+                        (name, quote! { ::std::option::Option<#inner> }, vec![
+                            (vec![RhsItem::Symbol(sym)], closure(quote! { |v| Ok(::std::option::Option::Some(v)) })?),
+                            (vec![], closure(quote! { || Ok(::std::option::Option::None) })?),
+                        ])
+                    }
+                    Repetition::Star | Repetition::Plus => {
+                        let name = match repetition {
+                            Repetition::Star => format_ident!("__{}Star", sym, span = span),
+                            _ => format_ident!("__{}Plus", sym, span = span),
+                        };
+
+                        let first = match repetition {
+                            Repetition::Star => (vec![], closure(quote! { || Ok(::std::vec::Vec::new()) })?),
+                            _ => (vec![RhsItem::Symbol(sym.clone())], closure(quote! { |v| Ok(::std::vec![v]) })?),
+                        };
+
+                        // Left recursive, so the parser stack does not grow with the repetition.
+                        let rest = (
+                            vec![RhsItem::Symbol(name.clone()), RhsItem::Symbol(sym)],
+                            closure(quote! { |mut vs, v| { vs.push(v); Ok(vs) } })?,
+                        );
+
+                        (name, quote! { ::std::vec::Vec<#inner> }, vec![first, rest])
+                    }
+                }
+            }
+        };
+
+        let attrs = vec![syn::parse_quote! { #[doc(hidden)] }];
+        self.symbols.push(SymbolDecl { attrs, name: name.clone(), ty: Some(syn::parse2(ty)?), display_name: None, terminal: false, helper: true });
+        helpers.push((key, name.clone()));
+
+        rules.extend(productions.into_iter().map(|(rhs, reducer)| RuleDecl { lhs: name.clone(), rhs, prec: None, reducer }));
+
+        Ok(name)
+    }
+
+    pub fn expand(mut self) -> syn::Result<TokenStream> {
+        self.validate()?;
+        self.desugar()?;
+
+        let vis = &self.vis;
+        let def = self.def.as_ref().unwrap();
+        let class = self.class.as_ref().unwrap();
+        let value = self.value.as_ref().unwrap();
+        let eos = self.eos.as_ref().unwrap();
+
+        // The symbol class enum
+        let class_variants = self.symbols.iter().map(|sym| {
+            let attrs = &sym.attrs;
+            let name = &sym.name;
+            quote! { #(#attrs)* #name }
+        });
+
+        let terminals = self.symbols.iter().filter(|sym| sym.terminal).map(|sym| &sym.name);
+
+        // The value enum
+        // Symbols without a display name are named after their variant.
+        let display_names = self.symbols.iter().filter_map(|sym| {
+            let name = &sym.name;
+            sym.display_name.as_ref().map(|display| quote! { #class::#name => ::std::string::String::from(#display) })
+        }).collect::<Vec<_>>();
+
+        let display_name = (!display_names.is_empty()).then(|| quote! {
+            #[allow(unreachable_patterns)]
+            fn display_name(&self) -> ::std::string::String {
+                match self {
+                    #(#display_names,)*
+                    _ => ::std::format!("{:?}", self)
+                }
+            }
+        });
+
+        let value_variants = self.symbols.iter().map(|sym| {
+            let name = &sym.name;
+            match &sym.ty {
+                Some(ty) => quote! { #name(#ty) },
+                None => quote! { #name },
+            }
+        });
+
+        let value_names = self.symbols.iter().map(|sym| {
+            let name = &sym.name;
+            let lit = name.to_string();
+            match &sym.ty {
+                Some(_) => quote! { #value::#name(_) => #lit },
+                None => quote! { #value::#name => #lit },
+            }
+        });
+
+        // Conversions from the value enum, symbols sharing the same type share the same conversion.
+        // The values of the helper non-terminals are only extracted by the generated reducers.
+        let mut types: Vec<(String, &Type, Vec<&Ident>)> = vec![];
+        for sym in self.symbols.iter().filter(|sym| !sym.helper) {
+            let Some(ty) = &sym.ty else {
+                continue;
+            };
+
+            let key = ty.to_token_stream().to_string();
+            match types.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, names)) => names.push(&sym.name),
+                None => types.push((key, ty, vec![&sym.name])),
+            }
+        }
+
+        let conversions = types.iter().map(|(_, ty, names)| {
+            quote! {
+                impl ::std::convert::TryFrom<#value> for #ty {
+                    type Error = ();
+
+                    fn try_from(value: #value) -> ::std::result::Result<Self, Self::Error> {
+                        match value {
+                            #(#value::#names(value))|* => ::std::result::Result::Ok(value),
+                            _ => ::std::result::Result::Err(())
+                        }
+                    }
+                }
+            }
+        });
+
+        // The rule set
+        let attrs = &self.attrs;
+        let rules_attrs = &self.rules_attrs;
+        let rules_vis = &self.rules_vis;
+        let rules_name = self.rules_name.as_ref().unwrap();
+        let context = self.context_type();
+
+        let error = self.error.as_ref().map(|error| {
+            let build = match self.terminal(error).unwrap().ty {
+                Some(_) => quote! { |err: &::yalp::parser::ParserError| #value::#error(::std::convert::From::from(err.clone())) },
+                None => quote! { |_: &::yalp::parser::ParserError| #value::#error },
+            };
+
+            quote! { .error_symbol(#class::#error, &#build) }
+        });
+
+        let precedences = self.precedences.iter().map(|(assoc, terminals)| {
+            let method = match assoc {
+                Associativity::Left => quote! { left },
+                Associativity::Right => quote! { right },
+                Associativity::NonAssoc => quote! { nonassoc },
+            };
+
+            quote! { .#method([#(#class::#terminals),*]) }
+        });
+
+        let entries = self.entries.iter().map(|(name, lhs)| {
+            let name = LitStr::new(&name.to_string(), name.span());
+            quote! { .entry(#name, #class::#lhs) }
+        });
+
+        let rules = self
+        .rules
+        .iter()
+        .map(|rule| self.expand_rule(rule))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            #(#attrs)*
+            #[derive(Clone, Debug, PartialEq)]
+            #vis enum #class {
+                #(#class_variants),*
+            }
+
+            impl ::yalp::parser::traits::ParserSymbolClass for #class {
+                fn is_terminal(&self) -> bool {
+                    ::std::matches!(self, #(#class::#terminals)|*)
+                }
+
+                fn eos() -> Self {
+                    #class::#eos
+                }
+
+                #display_name
+            }
+
+            #[derive(Clone)]
+            #vis enum #value {
+                #(#value_variants),*
+            }
+
+            impl ::std::fmt::Debug for #value {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(match self {
+                        #(#value_names),*
+                    })
+                }
+            }
+
+            #(#conversions)*
+
+            #vis struct #def;
+
+            impl ::yalp::symbol::traits::SymbolDefinition for #def {
+                type Class = #class;
+                type Value = #value;
+            }
+
+            ::yalp::__private::lazy_static! {
+                #(#rules_attrs)*
+                #rules_vis static ref #rules_name: ::yalp::parser::rule::ParserRuleSet<#def, #context> = ::yalp::parser::rule::ParserRuleSet::new()
+                #error
+                #(#precedences)*
+                #(#rules)*
+                #(#entries)*
+                .to_owned();
+            }
+        })
+    }
+
+    /// The type of the context passed to the reducers.
+    fn context_type(&self) -> TokenStream {
+        match &self.context {
+            Some(ty) => quote! { #ty },
+            None => quote! { () },
+        }
+    }
+
+    /// Generate the rule, with a reducer extracting the typed values of the symbols before calling the closure.
+    ///
+    /// The closure receives the context after the values, if it takes one more argument.
+    fn expand_rule(&self, rule: &RuleDecl) -> syn::Result<TokenStream> {
+        let def = self.def.as_ref().unwrap();
+        let class = self.class.as_ref().unwrap();
+        let value = self.value.as_ref().unwrap();
+        let context = self.context_type();
+
+        let lhs = &rule.lhs;
+        let lhs_type = self.symbol(lhs)?.value_type();
+        let rhs = rule.symbols();
+
+        let syms = (0..rhs.len())
+        .map(|i| format_ident!("__sym{}", i, span = Span::mixed_site()))
+        .collect::<Vec<_>>();
+
+        let values = (0..rhs.len())
+        .map(|i| format_ident!("__value{}", i, span = Span::mixed_site()))
+        .collect::<Vec<_>>();
+
+        let extractions = rhs
+        .iter()
+        .zip(syms.iter())
+        .zip(values.iter())
+        .map(|((name, sym), val)| {
+            let inner = Ident::new("__inner", Span::mixed_site());
+            let pattern = match self.symbol(name)?.ty {
+                Some(_) => quote! { #value::#name(#inner) => #inner },
+                None => quote! { #value::#name => () },
+            };
+
+            Ok(quote! {
+                let #val = match #sym.value {
+                    #pattern,
+                    _ => return ::std::result::Result::Err(::yalp::parser::ParserError::wrong_value(#sym))
+                };
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+        // Annotate the closure's arguments with the types of the symbols, and of the context.
+        let mut params = rule
+        .reducer
+        .inputs
+        .iter()
+        .zip(rhs.iter())
+        .map(|(pat, name)| {
+            let ty = self.symbol(name)?.value_type();
+            Ok(match pat {
+                Pat::Type(_) => quote! { #pat },
+                _ => quote! { #pat: #ty },
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+        let ctx = Ident::new("__ctx", Span::mixed_site());
+        let mut args = values.iter().map(|val| quote! { #val }).collect::<Vec<_>>();
+
+        if let Some(pat) = rule.reducer.inputs.iter().nth(rhs.len()) {
+            params.push(match pat {
+                Pat::Type(_) => quote! { #pat },
+                _ => quote! { #pat: &mut #context },
+            });
+            args.push(quote! { #ctx });
+        }
+
+        let body = &rule.reducer.body;
+        let prec = rule.prec.as_ref().map(|prec| quote! { .prec(#class::#prec) });
+        let reducer = Ident::new("__reducer", Span::mixed_site());
+        let count = rhs.len();
+
+        Ok(quote! {
+            .add_with_context(
+                #class::#lhs, ::std::vec::Vec::<#class>::from([#(#class::#rhs),*]),
+                &|syms: ::std::vec::Vec<::yalp::symbol::Sym<#def>>, #ctx: &mut #context| {
+                    let [#(#syms),*]: [::yalp::symbol::Sym<#def>; #count] = match syms.try_into() {
+                        ::std::result::Result::Ok(syms) => syms,
+                        ::std::result::Result::Err(_) => ::std::unreachable!("the parser pops as many symbols as the rule has")
+                    };
+
+                    #(#extractions)*
+
+                    let #reducer = |#(#params),*| -> ::std::result::Result<#lhs_type, ::yalp::parser::ParserError> { #body };
+                    #reducer(#(#args),*).map(#value::#lhs)
+                }
+            )
+            #prec
+        })
+    }
+}
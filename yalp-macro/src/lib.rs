@@ -0,0 +1,67 @@
+mod grammar;
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+/// Declare a grammar, and generate its symbols and rule set.
+///
+/// ```ignore
+/// yalp::grammar! {
+///     pub symbols ExprDef { class: Class, value: Value }
+///     pub rules RULES;
+///
+///     terminals {
+///         EOS,
+///         Plus,
+///         Number: u32
+///     }
+///
+///     nonterminals {
+///         Expr: u32
+///     }
+///
+///     eos EOS;
+///     left Plus;
+///
+///     Expr -> Expr Plus Expr => |lhs, _, rhs| Ok(lhs + rhs);
+///     Expr -> Number => |n| Ok(n);
+/// }
+/// ```
+///
+/// The first rule is the root of the grammar.
+///
+/// An entry point may be declared on a non-terminal, so the parsing may start from it instead of the root:
+///
+/// ```ignore
+/// entry fragment Fragment;
+/// ```
+///
+/// The reducers may receive the context given to the parser, see `ParserRuleSet::add_with_context`,
+/// as an extra argument after the values of the symbols, once its type is declared:
+///
+/// ```ignore
+/// context Interner;
+///
+/// Expr -> Ident => |name, interner| Ok(interner.intern(name));
+/// ```
+///
+/// A symbol may be given a name to display in error messages, such as `RightAngle: syn::Token![>] = "`>`"`.
+///
+/// The right-hand side of a rule may be empty, and use EBNF operators, which are desugared into helper non-terminals:
+/// - `X?` produces an `Option` of the value of X,
+/// - `X*` and `X+` produce a `Vec` of the values of X,
+/// - `(X Y)` groups the symbols, and produces a tuple of their values.
+///
+/// ```ignore
+/// List -> Item (Comma Item)* Comma? => |first, rest, _| Ok(...);
+/// Empty -> => || Ok(());
+/// ```
+#[proc_macro]
+pub fn grammar(input: TokenStream) -> TokenStream {
+    let grammar = parse_macro_input!(input as grammar::Grammar);
+
+    grammar
+    .expand()
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
@@ -0,0 +1,204 @@
+//! Lossless concrete syntax trees
+//!
+//! Each token keeps its text, and the text skipped by the lexer before it, such as whitespaces and comments, as trivia.
+//! Each node is labelled by the class of the non-terminal it derives, see [crate::parser::lr::LrParser::parse_cst].
+use std::{fmt::Debug, marker::PhantomData};
+
+use crate::{parser::ParserError, span::Span, symbol::{traits::SymbolDefinition, Sym}};
+
+/// A text skipped by the lexer
+#[derive(Clone, Debug)]
+pub struct Trivia {
+    pub span: Span,
+    pub text: String
+}
+
+/// A token, with its text and the trivia preceding it.
+pub struct CstToken<SymDef: SymbolDefinition> {
+    pub class: SymDef::Class,
+    pub span: Span,
+    pub text: String,
+    pub leading_trivia: Vec<Trivia>
+}
+
+impl<SymDef> Clone for CstToken<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { class: self.class.clone(), span: self.span.clone(), text: self.text.clone(), leading_trivia: self.leading_trivia.clone() }
+    }
+}
+
+impl<SymDef> Debug for CstToken<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CstToken")
+            .field("class", &self.class)
+            .field("span", &self.span)
+            .field("text", &self.text)
+            .field("leading_trivia", &self.leading_trivia)
+        .finish()
+    }
+}
+
+impl<SymDef> CstToken<SymDef> where SymDef: SymbolDefinition {
+    /// Write the trivia, then the text of the token.
+    fn write_source(&self, out: &mut String) {
+        self.leading_trivia.iter().for_each(|trivia| out.push_str(&trivia.text));
+        out.push_str(&self.text);
+    }
+}
+
+/// A node of the tree
+pub enum CstNode<SymDef: SymbolDefinition> {
+    /// A non-terminal, and the nodes it derives.
+    Node { class: SymDef::Class, span: Span, children: Vec<CstNode<SymDef>> },
+    Token(CstToken<SymDef>),
+    /// The error symbol, standing for the syntax error recovered from.
+    Error { class: SymDef::Class, error: ParserError }
+}
+
+impl<SymDef> Clone for CstNode<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Node { class, span, children } => Self::Node { class: class.clone(), span: span.clone(), children: children.clone() },
+            Self::Token(tok) => Self::Token(tok.clone()),
+            Self::Error { class, error } => Self::Error { class: class.clone(), error: error.clone() }
+        }
+    }
+}
+
+impl<SymDef> Debug for CstNode<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Node { class, span, children } => f.debug_struct("Node").field("class", class).field("span", span).field("children", children).finish(),
+            Self::Token(tok) => f.debug_tuple("Token").field(tok).finish(),
+            Self::Error { class, error } => f.debug_struct("Error").field("class", class).field("error", error).finish()
+        }
+    }
+}
+
+impl<SymDef> CstNode<SymDef> where SymDef: SymbolDefinition {
+    pub fn class(&self) -> &SymDef::Class {
+        match self {
+            Self::Node { class, .. } => class,
+            Self::Token(tok) => &tok.class,
+            Self::Error { class, .. } => class
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Node { span, .. } => span.clone(),
+            Self::Token(tok) => tok.span.clone(),
+            Self::Error { error, .. } => error.span()
+        }
+    }
+
+    /// The nodes derived by the non-terminal, a token has none.
+    pub fn children(&self) -> &[CstNode<SymDef>] {
+        match self {
+            Self::Node { children, .. } => children,
+            _ => &[]
+        }
+    }
+
+    /// Returns the tokens of the tree, in order.
+    pub fn tokens(&self) -> Vec<&CstToken<SymDef>> {
+        match self {
+            Self::Token(tok) => vec![tok],
+            _ => self.children().iter().flat_map(|child| child.tokens()).collect()
+        }
+    }
+
+    /// Returns the text of the tree, trivia included.
+    pub fn to_source(&self) -> String {
+        let mut out = String::new();
+        self.tokens().into_iter().for_each(|tok| tok.write_source(&mut out));
+        out
+    }
+}
+
+/// The tree of a whole input
+pub struct Cst<SymDef: SymbolDefinition> {
+    pub root: CstNode<SymDef>,
+    /// The end of stream token, holding the trivia following the last token.
+    pub eos: CstToken<SymDef>
+}
+
+impl<SymDef> Debug for Cst<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cst").field("root", &self.root).field("eos", &self.eos).finish()
+    }
+}
+
+impl<SymDef> Cst<SymDef> where SymDef: SymbolDefinition {
+    /// Returns the source text of the input.
+    ///
+    /// It is the input as is, unless tokens were discarded while recovering from syntax errors.
+    pub fn to_source(&self) -> String {
+        let mut out = self.root.to_source();
+        self.eos.write_source(&mut out);
+        out
+    }
+}
+
+/// Symbols whose values are the nodes of the tree, used to drive the parser in place of the user's definition.
+pub(crate) struct CstDefinition<SymDef>(PhantomData<SymDef>);
+
+impl<SymDef> SymbolDefinition for CstDefinition<SymDef> where SymDef: SymbolDefinition {
+    type Class = SymDef::Class;
+    type Value = CstValue<SymDef>;
+}
+
+/// The value of a symbol, while parsing.
+///
+/// The class of a reduced symbol is unknown to its reducer, so its node is built once its parent is reduced.
+pub(crate) enum CstValue<SymDef: SymbolDefinition> {
+    Token(CstToken<SymDef>),
+    Children(Vec<CstNode<SymDef>>),
+    Error(ParserError)
+}
+
+impl<SymDef> Clone for CstValue<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Token(tok) => Self::Token(tok.clone()),
+            Self::Children(children) => Self::Children(children.clone()),
+            Self::Error(error) => Self::Error(error.clone())
+        }
+    }
+}
+
+impl<SymDef> Debug for CstValue<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Token(tok) => f.debug_tuple("Token").field(tok).finish(),
+            Self::Children(children) => f.debug_tuple("Children").field(children).finish(),
+            Self::Error(error) => f.debug_tuple("Error").field(error).finish()
+        }
+    }
+}
+
+impl<SymDef> From<CstToken<SymDef>> for Sym<CstDefinition<SymDef>> where SymDef: SymbolDefinition {
+    fn from(tok: CstToken<SymDef>) -> Self {
+        Sym { span: tok.span.clone(), class: tok.class.clone(), value: CstValue::Token(tok) }
+    }
+}
+
+impl<SymDef> From<Sym<CstDefinition<SymDef>>> for CstNode<SymDef> where SymDef: SymbolDefinition {
+    fn from(sym: Sym<CstDefinition<SymDef>>) -> Self {
+        match sym.value {
+            CstValue::Token(tok) => Self::Token(tok),
+            CstValue::Children(children) => Self::Node { class: sym.class, span: sym.span, children },
+            CstValue::Error(error) => Self::Error { class: sym.class, error }
+        }
+    }
+}
+
+/// Reduce a rule by gathering the nodes of its symbols.
+pub(crate) fn reduce<SymDef: SymbolDefinition>(syms: Vec<Sym<CstDefinition<SymDef>>>) -> Result<CstValue<SymDef>, ParserError> {
+    Ok(CstValue::Children(syms.into_iter().map(CstNode::from).collect()))
+}
+
+/// Build the value of the error symbol.
+pub(crate) fn error<SymDef: SymbolDefinition>(error: &ParserError) -> CstValue<SymDef> {
+    CstValue::Error(error.clone())
+}
@@ -0,0 +1,224 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::regex::{Regex, next_char, prev_char};
+
+/// Non-deterministic automaton, built from the patterns by Thompson's construction.
+#[derive(Default)]
+struct Nfa {
+    states: Vec<NfaState>
+}
+
+#[derive(Default)]
+struct NfaState {
+    epsilons: Vec<usize>,
+    transitions: Vec<(char, char, usize)>,
+    /// The pattern recognized when reaching the state
+    accept: Option<usize>
+}
+
+impl Nfa {
+    fn new_state(&mut self) -> usize {
+        self.states.push(NfaState::default());
+        self.states.len() - 1
+    }
+
+    fn epsilon(&mut self, from: usize, to: usize) {
+        self.states[from].epsilons.push(to);
+    }
+
+    /// Add the fragment recognizing the regex, returns its entry and exit states.
+    fn fragment(&mut self, regex: &Regex) -> (usize, usize) {
+        let start = self.new_state();
+
+        let end = match regex {
+            Regex::Empty => start,
+            Regex::Class(ranges) => {
+                let end = self.new_state();
+                for (lo, hi) in ranges {
+                    self.states[start].transitions.push((*lo, *hi, end));
+                }
+                end
+            },
+            Regex::Concat(seq) => seq.iter().fold(start, |last, regex| {
+                let (entry, exit) = self.fragment(regex);
+                self.epsilon(last, entry);
+                exit
+            }),
+            Regex::Alt(alts) => {
+                let end = self.new_state();
+                for regex in alts {
+                    let (entry, exit) = self.fragment(regex);
+                    self.epsilon(start, entry);
+                    self.epsilon(exit, end);
+                }
+                end
+            },
+            Regex::Star(regex) => {
+                let (entry, exit) = self.fragment(regex);
+                self.epsilon(start, entry);
+                self.epsilon(exit, start);
+                start
+            },
+            Regex::Plus(regex) => {
+                let (entry, exit) = self.fragment(regex);
+                self.epsilon(start, entry);
+                self.epsilon(exit, entry);
+                exit
+            },
+            Regex::Optional(regex) => {
+                let (entry, exit) = self.fragment(regex);
+                self.epsilon(start, entry);
+                self.epsilon(start, exit);
+                exit
+            }
+        };
+
+        (start, end)
+    }
+
+    /// The states reachable from the given ones without consuming any character, sorted.
+    fn closure(&self, states: impl IntoIterator<Item=usize>) -> Vec<usize> {
+        let mut closure: Vec<usize> = vec![];
+        let mut queue: VecDeque<usize> = states.into_iter().collect();
+
+        while let Some(state) = queue.pop_front() {
+            if closure.contains(&state) {
+                continue;
+            }
+            closure.push(state);
+            queue.extend(self.states[state].epsilons.iter().copied());
+        }
+
+        closure.sort();
+        closure
+    }
+}
+
+/// Deterministic automaton recognizing the patterns.
+#[derive(Clone, Debug)]
+pub(super) struct Dfa {
+    states: Vec<DfaState>
+}
+
+#[derive(Clone, Debug)]
+struct DfaState {
+    /// Sorted, non-overlapping, character ranges
+    transitions: Vec<(char, char, usize)>,
+    /// The pattern recognized when reaching the state, the first declared one wins.
+    accept: Option<usize>
+}
+
+impl Dfa {
+    /// Build the automaton recognizing any of the patterns, by the subset construction.
+    pub fn build(patterns: &[Regex]) -> Self {
+        let mut nfa = Nfa::default();
+        let root = nfa.new_state();
+
+        for (id, regex) in patterns.iter().enumerate() {
+            let (entry, exit) = nfa.fragment(regex);
+            nfa.epsilon(root, entry);
+            nfa.states[exit].accept = Some(id);
+        }
+
+        let mut sets: Vec<Vec<usize>> = vec![];
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        let start = nfa.closure([root]);
+        ids.insert(start.clone(), 0);
+        sets.push(start);
+        queue.push_back(0);
+
+        let mut states = vec![];
+
+        while let Some(id) = queue.pop_front() {
+            let set = &sets[id];
+            let accept = set.iter().filter_map(|s| nfa.states[*s].accept).min();
+            let moves = set.iter().flat_map(|s| nfa.states[*s].transitions.iter().copied()).collect::<Vec<_>>();
+
+            let mut transitions = vec![];
+
+            for (lo, hi) in disjoint_ranges(&moves) {
+                let targets = nfa.closure(
+                    moves
+                    .iter()
+                    .filter(|(l, h, _)| *l <= lo && hi <= *h)
+                    .map(|(_, _, target)| *target)
+                );
+
+                let next = match ids.get(&targets) {
+                    Some(next) => *next,
+                    None => {
+                        let next = sets.len();
+                        ids.insert(targets.clone(), next);
+                        sets.push(targets);
+                        queue.push_back(next);
+                        next
+                    }
+                };
+
+                // Merge with the previous range if contiguous and leading to the same state.
+                match transitions.last_mut() {
+                    Some((_, last_hi, last_next)) if *last_next == next && next_char(*last_hi) == Some(lo) => *last_hi = hi,
+                    _ => transitions.push((lo, hi, next))
+                }
+            }
+
+            states.push((id, DfaState { transitions, accept }));
+        }
+
+        states.sort_by_key(|(id, _)| *id);
+        Self { states: states.into_iter().map(|(_, state)| state).collect() }
+    }
+
+    /// Returns the first pattern matching the empty string, if any.
+    pub fn empty_match(&self) -> Option<usize> {
+        self.states[0].accept
+    }
+
+    /// Returns the length in bytes of the longest match at the start of the input, and the pattern recognized.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, usize)> {
+        let mut state = &self.states[0];
+        let mut matched = state.accept.map(|pattern| (0, pattern));
+
+        for (offset, c) in input.char_indices() {
+            let index = state.transitions.partition_point(|(_, hi, _)| *hi < c);
+
+            match state.transitions.get(index) {
+                Some((lo, _, next)) if *lo <= c => state = &self.states[*next],
+                _ => break
+            }
+
+            if let Some(pattern) = state.accept {
+                matched = Some((offset + c.len_utf8(), pattern));
+            }
+        }
+
+        matched
+    }
+}
+
+/// Split the ranges of the transitions into disjoint ones, each one being either contained or disjoint of any of them.
+fn disjoint_ranges(moves: &[(char, char, usize)]) -> Vec<(char, char)> {
+    let mut bounds = moves
+    .iter()
+    .flat_map(|(lo, hi, _)| [Some(*lo), next_char(*hi)])
+    .collect::<Vec<_>>();
+
+    // None stands for the end of the characters, and goes last.
+    bounds.sort_by_key(|bound| bound.map(|c| c as u32).unwrap_or(u32::MAX));
+    bounds.dedup();
+
+    bounds
+    .windows(2)
+    .filter_map(|window| {
+        let lo = window[0]?;
+        let hi = match window[1] {
+            Some(next) => prev_char(next)?,
+            None => char::MAX
+        };
+        Some((lo, hi))
+    })
+    .filter(|(lo, hi)| moves.iter().any(|(l, h, _)| l <= lo && hi <= h))
+    .collect()
+}
@@ -0,0 +1,326 @@
+use std::fmt::Display;
+
+use crate::{cst::{CstToken, Trivia}, parser::traits::ParserSymbolClass, source::SourceFile, span::Span, symbol::{traits::SymbolDefinition, Sym}};
+
+use self::{automaton::Dfa, regex::RegexError, rule::{LexerAction, LexerRuleSet}};
+
+mod automaton;
+pub mod regex;
+pub mod rule;
+
+#[derive(Clone, Debug)]
+pub struct LexerError {
+    pub span:    Span,
+    pub message: String
+}
+
+#[cfg(feature = "proc-macro")]
+impl From<syn::Error> for LexerError {
+    fn from(value: syn::Error) -> Self {
+        Self {
+            span: value.span().into(),
+            message:  value.to_string()
+        }
+    }
+}
+
+#[cfg(feature = "proc-macro")]
+impl Into<syn::Error> for LexerError {
+    fn into(self) -> syn::Error {
+        syn::Error::new(self.span.into(), self.message)
+    }
+}
+
+/// The rules cannot be turned into a lexer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexerGenerationError {
+    InvalidPattern(RegexError),
+    /// The pattern of the rule matches the empty string, the lexer would loop forever.
+    EmptyMatch(usize),
+    /// The value of the end of stream token is not declared.
+    MissingEos
+}
+
+impl Display for LexerGenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPattern(err) => Display::fmt(err, f),
+            Self::EmptyMatch(rule) => write!(f, "the pattern of the lexer rule {} matches the empty string", rule),
+            Self::MissingEos => write!(f, "the value of the end of stream token is not declared"),
+        }
+    }
+}
+
+impl std::error::Error for LexerGenerationError {}
+
+impl From<RegexError> for LexerGenerationError {
+    fn from(value: RegexError) -> Self {
+        Self::InvalidPattern(value)
+    }
+}
+
+/// A lexer recognizing the tokens of plain text, generated from its rules.
+pub struct Lexer<'a, SymDef> where SymDef: SymbolDefinition {
+    rules: &'a LexerRuleSet<SymDef>,
+    dfa: Dfa
+}
+
+impl<'a, SymDef> Lexer<'a, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Generate the lexer
+    pub fn generate(rules: &'a LexerRuleSet<SymDef>) -> Result<Self, LexerGenerationError> {
+        if rules.get_eos().is_none() {
+            return Err(LexerGenerationError::MissingEos);
+        }
+
+        let patterns = rules
+            .iter()
+            .map(|rule| rule.pattern.compile())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let dfa = Dfa::build(&patterns);
+
+        if let Some(rule) = dfa.empty_match() {
+            return Err(LexerGenerationError::EmptyMatch(rule));
+        }
+
+        Ok(Self { rules, dfa })
+    }
+
+    /// Split the input into tokens, ended by the end of stream token.
+    /// 
+    /// The spans are the byte offsets of the tokens within the input.
+    pub fn tokenize<'b>(&'b self, input: &'b str) -> Tokens<'a, 'b, SymDef> {
+        Tokens { lexer: self, input, start: 0, offset: 0, exhausted: false }
+    }
+
+    /// Split the file into tokens, ended by the end of stream token.
+    /// 
+    /// The spans are the offsets of the tokens within the source map of the file.
+    pub fn tokenize_file<'b>(&'b self, file: &'b SourceFile) -> Tokens<'a, 'b, SymDef> {
+        Tokens { lexer: self, input: file.src(), start: file.start(), offset: 0, exhausted: false }
+    }
+
+    /// Split the input into tokens, keeping the skipped text, such as whitespaces and comments, as their leading trivia.
+    /// 
+    /// The text following the last token is the trivia of the end of stream token, see [crate::cst].
+    pub fn tokenize_lossless<'b>(&'b self, input: &'b str) -> LosslessTokens<'a, 'b, SymDef> {
+        LosslessTokens { tokens: self.tokenize(input) }
+    }
+}
+
+/// The tokens of the input
+pub struct Tokens<'a, 'b, SymDef> where SymDef: SymbolDefinition {
+    lexer: &'b Lexer<'a, SymDef>,
+    input: &'b str,
+    /// The offset of the input, within its source map
+    start: usize,
+    offset: usize,
+    exhausted: bool
+}
+
+impl<'a, 'b, SymDef> Tokens<'a, 'b, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Match the next lexeme, be it a token or a skipped text.
+    fn next_lexeme(&mut self) -> Option<Result<Lexeme<'b, SymDef>, LexerError>> {
+        if self.exhausted {
+            return None;
+        }
+
+        let rest = &self.input[self.offset..];
+        let from = self.start + self.offset;
+
+        if rest.is_empty() {
+            self.exhausted = true;
+            let eos = self.lexer.rules.get_eos().expect("the lexer is generated with an end of stream value");
+            return Some(Ok(Lexeme::Token(Sym::new(Span::Interval { from, to: from }, SymDef::Class::eos(), eos("")), "")));
+        }
+
+        let Some((len, rule_id)) = self.lexer.dfa.longest_match(rest) else {
+            self.exhausted = true;
+            let c = rest.chars().next().unwrap();
+            return Some(Err(LexerError {
+                span: Span::Interval { from, to: from + c.len_utf8() },
+                message: format!("unexpected character {:?}", c)
+            }));
+        };
+
+        self.offset += len;
+        let span = Span::Interval { from, to: from + len };
+        let text = &rest[..len];
+
+        match &self.lexer.rules.get(rule_id).unwrap().action {
+            LexerAction::Skip => Some(Ok(Lexeme::Skipped(span, text))),
+            LexerAction::Token(class, value) => Some(Ok(Lexeme::Token(Sym::new(span, class.clone(), value(text)), text)))
+        }
+    }
+}
+
+impl<'a, 'b, SymDef> Iterator for Tokens<'a, 'b, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    type Item = Result<Sym<SymDef>, LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_lexeme()? {
+                Ok(Lexeme::Token(sym, _)) => return Some(Ok(sym)),
+                Ok(Lexeme::Skipped(..)) => continue,
+                Err(err) => return Some(Err(err))
+            }
+        }
+    }
+}
+
+/// A match of the lexer
+enum Lexeme<'b, SymDef: SymbolDefinition> {
+    Token(Sym<SymDef>, &'b str),
+    /// A text matched by a skip rule
+    Skipped(Span, &'b str)
+}
+
+/// The tokens of the input, carrying the skipped text preceding them as trivia.
+pub struct LosslessTokens<'a, 'b, SymDef> where SymDef: SymbolDefinition {
+    tokens: Tokens<'a, 'b, SymDef>
+}
+
+impl<'a, 'b, SymDef> Iterator for LosslessTokens<'a, 'b, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    type Item = Result<CstToken<SymDef>, LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut leading_trivia = vec![];
+
+        loop {
+            match self.tokens.next_lexeme()? {
+                Ok(Lexeme::Token(sym, text)) => return Some(Ok(CstToken {
+                    class: sym.class,
+                    span: sym.span,
+                    text: text.to_string(),
+                    leading_trivia
+                })),
+                Ok(Lexeme::Skipped(span, text)) => leading_trivia.push(Trivia { span, text: text.to_string() }),
+                Err(err) => return Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::{parser::traits::ParserSymbolClass, span::Span, symbol::traits::SymbolDefinition};
+
+    use super::{rule::LexerRuleSet, Lexer, LexerGenerationError, regex::Regex};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Class {
+        EOS,
+        If,
+        Ident,
+        Number,
+        Assign,
+        Equal,
+        Str
+    }
+
+    impl ParserSymbolClass for Class {
+        fn is_terminal(&self) -> bool {
+            true
+        }
+
+        fn eos() -> Self {
+            Class::EOS
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Value {
+        None,
+        Text(String),
+        Number(u32)
+    }
+
+    struct SymDef;
+
+    impl SymbolDefinition for SymDef {
+        type Class = Class;
+        type Value = Value;
+    }
+
+    lazy_static! {
+        static ref RULES: LexerRuleSet<SymDef> = LexerRuleSet::new()
+            .eos(&|_| Value::None)
+            .skip(r"[ \t\n]+")
+            .skip(r"//[^\n]*")
+            .literal(Class::If, "if", &|_| Value::None)
+            .token(Class::Ident, r"[a-zA-Z_]\w*", &|s| Value::Text(s.to_string()))
+            .token(Class::Number, r"\d+", &|s| Value::Number(s.parse().unwrap()))
+            .literal(Class::Equal, "==", &|_| Value::None)
+            .literal(Class::Assign, "=", &|_| Value::None)
+            .token(Class::Str, r#""([^"\\]|\\.)*""#, &|s| Value::Text(s.to_string()))
+            .to_owned();
+    }
+
+    fn tokenize(input: &str) -> Vec<(Class, Value)> {
+        let lexer = Lexer::generate(&RULES).unwrap();
+        lexer
+            .tokenize(input)
+            .map(|tok| tok.map(|sym| (sym.class, sym.value)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn longest_match_and_priority() {
+        let tokens = tokenize("if iffy == 42 // comment\n x = \"a \\\" b\"");
+
+        assert_eq!(tokens, vec![
+            (Class::If, Value::None),
+            (Class::Ident, Value::Text("iffy".into())),
+            (Class::Equal, Value::None),
+            (Class::Number, Value::Number(42)),
+            (Class::Ident, Value::Text("x".into())),
+            (Class::Assign, Value::None),
+            (Class::Str, Value::Text("\"a \\\" b\"".into())),
+            (Class::EOS, Value::None)
+        ]);
+    }
+
+    #[test]
+    fn token_spans() {
+        let lexer = Lexer::generate(&RULES).unwrap();
+        let spans = lexer
+            .tokenize("x  = 1")
+            .map(|tok| match tok.unwrap().span {
+                Span::Interval { from, to } => (from, to),
+                span => panic!("unexpected span {:?}", span)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(spans, vec![(0, 1), (3, 4), (5, 6), (6, 6)]);
+    }
+
+    #[test]
+    fn unexpected_character() {
+        let lexer = Lexer::generate(&RULES).unwrap();
+        let err = lexer.tokenize("x = é").find_map(|tok| tok.err()).unwrap();
+
+        assert!(matches!(err.span, Span::Interval { from: 4, to: 6 }));
+    }
+
+    #[test]
+    fn reject_invalid_rules() {
+        let empty = LexerRuleSet::<SymDef>::new()
+            .eos(&|_| Value::None)
+            .token(Class::Ident, "a*", &|_| Value::None)
+            .to_owned();
+
+        assert!(matches!(Lexer::generate(&empty), Err(LexerGenerationError::EmptyMatch(0))));
+
+        let invalid = LexerRuleSet::<SymDef>::new()
+            .eos(&|_| Value::None)
+            .token(Class::Ident, "(a", &|_| Value::None)
+            .to_owned();
+
+        assert!(matches!(Lexer::generate(&invalid), Err(LexerGenerationError::InvalidPattern(_))));
+        assert!(Regex::parse("[z-a]").is_err());
+        assert!(Regex::parse("*").is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt::Display;
+
+/// A parsed token pattern
+///
+/// Supported syntax:
+/// - literal characters, and escaped ones: \\ \. \* \n \t ...
+/// - any character but a newline: .
+/// - character classes: [a-z_] [^"] \d \w \s
+/// - grouping: (ab)
+/// - alternation: a|b
+/// - repetition: a* a+ a?
+#[derive(Clone, Debug, PartialEq)]
+pub enum Regex {
+    /// Matches the empty string
+    Empty,
+    /// Matches a single character within one of the inclusive ranges
+    Class(Vec<(char, char)>),
+    Concat(Vec<Regex>),
+    Alt(Vec<Regex>),
+    Star(Box<Regex>),
+    Plus(Box<Regex>),
+    Optional(Box<Regex>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegexError {
+    pub pattern: String,
+    /// The position of the faulty character in the pattern
+    pub position: usize,
+    pub message: String
+}
+
+impl Display for RegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid pattern \"{}\" at {}: {}", self.pattern, self.position, self.message)
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+impl Regex {
+    /// Parse the pattern
+    pub fn parse(pattern: &str) -> Result<Self, RegexError> {
+        let mut parser = RegexParser { pattern, chars: pattern.chars().collect(), pos: 0 };
+        let regex = parser.alternation()?;
+
+        match parser.peek() {
+            None => Ok(regex),
+            Some(')') => Err(parser.error("unbalanced parenthesis")),
+            Some(_) => Err(parser.error("unexpected character"))
+        }
+    }
+
+    /// Matches the string as is
+    pub fn literal(value: &str) -> Self {
+        Self::Concat(value.chars().map(|c| Self::Class(vec![(c, c)])).collect())
+    }
+}
+
+struct RegexParser<'a> {
+    pattern: &'a str,
+    chars: Vec<char>,
+    pos: usize
+}
+
+impl<'a> RegexParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn error(&self, message: &str) -> RegexError {
+        RegexError { pattern: self.pattern.to_string(), position: self.pos, message: message.to_string() }
+    }
+
+    /// alternation := concatenation ('|' concatenation)*
+    fn alternation(&mut self) -> Result<Regex, RegexError> {
+        let mut alts = vec![self.concatenation()?];
+
+        while self.peek() == Some('|') {
+            self.next();
+            alts.push(self.concatenation()?);
+        }
+
+        Ok(if alts.len() == 1 { alts.remove(0) } else { Regex::Alt(alts) })
+    }
+
+    /// concatenation := repetition*
+    fn concatenation(&mut self) -> Result<Regex, RegexError> {
+        let mut seq = vec![];
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            seq.push(self.repetition()?);
+        }
+
+        Ok(match seq.len() {
+            0 => Regex::Empty,
+            1 => seq.remove(0),
+            _ => Regex::Concat(seq)
+        })
+    }
+
+    /// repetition := atom ('*' | '+' | '?')*
+    fn repetition(&mut self) -> Result<Regex, RegexError> {
+        let mut regex = self.atom()?;
+
+        loop {
+            regex = match self.peek() {
+                Some('*') => Regex::Star(Box::new(regex)),
+                Some('+') => Regex::Plus(Box::new(regex)),
+                Some('?') => Regex::Optional(Box::new(regex)),
+                _ => return Ok(regex)
+            };
+            self.next();
+        }
+    }
+
+    /// atom := '(' alternation ')' | '[' class ']' | '.' | '\' escape | char
+    fn atom(&mut self) -> Result<Regex, RegexError> {
+        match self.peek() {
+            Some('(') => {
+                self.next();
+                let regex = self.alternation()?;
+                if self.next() != Some(')') {
+                    self.pos -= 1;
+                    return Err(self.error("expecting a closing parenthesis"));
+                }
+                Ok(regex)
+            },
+            Some('[') => {
+                self.next();
+                self.class()
+            },
+            Some('.') => {
+                self.next();
+                Ok(Regex::Class(complement(&[('\n', '\n')])))
+            },
+            Some('\\') => {
+                self.next();
+                Ok(Regex::Class(self.escape()?))
+            },
+            Some('*' | '+' | '?') => Err(self.error("nothing to repeat")),
+            Some(c) => {
+                self.next();
+                Ok(Regex::Class(vec![(c, c)]))
+            },
+            None => Err(self.error("unexpected end of pattern"))
+        }
+    }
+
+    /// class := '^'? (char | char '-' char | '\' escape)* ']'
+    fn class(&mut self) -> Result<Regex, RegexError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.next();
+        }
+
+        let mut ranges = vec![];
+
+        loop {
+            let lo = match self.next() {
+                Some(']') => break,
+                Some('\\') => {
+                    let escaped = self.escape()?;
+                    match escaped[..] {
+                        [(lo, hi)] if lo == hi => lo,
+                        _ => {
+                            ranges.extend(escaped);
+                            continue;
+                        }
+                    }
+                },
+                Some(c) => c,
+                None => return Err(self.error("expecting a closing bracket"))
+            };
+
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|c| *c != ']') {
+                self.next();
+                let hi = match self.next() {
+                    Some('\\') => match self.escape()?[..] {
+                        [(hi, _)] => hi,
+                        _ => return Err(self.error("invalid range bound"))
+                    },
+                    Some(c) => c,
+                    None => return Err(self.error("expecting a closing bracket"))
+                };
+
+                if hi < lo {
+                    return Err(self.error("invalid range, the bounds are reversed"));
+                }
+
+                ranges.push((lo, hi));
+            } else {
+                ranges.push((lo, lo));
+            }
+        }
+
+        Ok(Regex::Class(if negated { complement(&ranges) } else { ranges }))
+    }
+
+    /// The character following a backslash
+    fn escape(&mut self) -> Result<Vec<(char, char)>, RegexError> {
+        let c = self.next().ok_or_else(|| self.error("unexpected end of pattern"))?;
+
+        Ok(match c {
+            'n' => vec![('\n', '\n')],
+            't' => vec![('\t', '\t')],
+            'r' => vec![('\r', '\r')],
+            '0' => vec![('\0', '\0')],
+            'd' => vec![('0', '9')],
+            'w' => vec![('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')],
+            's' => vec![('\t', '\n'), ('\r', '\r'), (' ', ' ')],
+            c if c.is_alphanumeric() => {
+                self.pos -= 1;
+                return Err(self.error("unknown escape sequence"));
+            },
+            c => vec![(c, c)]
+        })
+    }
+}
+
+/// Returns the characters which are not in the ranges
+pub(super) fn complement(ranges: &[(char, char)]) -> Vec<(char, char)> {
+    let mut ranges = ranges.to_vec();
+    ranges.sort();
+
+    let mut result = vec![];
+    let mut lo = Some('\0');
+
+    for (start, end) in ranges {
+        if let Some(l) = lo {
+            if l < start {
+                result.push((l, prev_char(start).unwrap()));
+            }
+        }
+
+        if lo.is_some_and(|l| l <= end) {
+            lo = next_char(end);
+        }
+    }
+
+    if let Some(l) = lo {
+        result.push((l, char::MAX));
+    }
+
+    result
+}
+
+/// The next valid character, skipping the surrogates
+pub(super) fn next_char(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        c => char::from_u32(c as u32 + 1)
+    }
+}
+
+/// The previous valid character, skipping the surrogates
+pub(super) fn prev_char(c: char) -> Option<char> {
+    match c {
+        '\u{E000}' => Some('\u{D7FF}'),
+        '\0' => None,
+        c => char::from_u32(c as u32 - 1)
+    }
+}
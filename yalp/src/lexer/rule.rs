@@ -0,0 +1,114 @@
+use crate::symbol::traits::SymbolDefinition;
+
+use super::regex::{Regex, RegexError};
+
+/// Build the value of the token from the matched text.
+pub type TokenValueFunc<SymDef> = &'static (dyn Fn(&str) -> <SymDef as SymbolDefinition>::Value + Sync + Send);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexerPattern {
+    Regex(String),
+    Literal(String)
+}
+
+impl LexerPattern {
+    pub fn compile(&self) -> Result<Regex, RegexError> {
+        match self {
+            Self::Regex(pattern) => Regex::parse(pattern),
+            Self::Literal(value) => Ok(Regex::literal(value))
+        }
+    }
+}
+
+pub enum LexerAction<SymDef: SymbolDefinition> {
+    /// Produce a token of the class
+    Token(SymDef::Class, TokenValueFunc<SymDef>),
+    /// Discard the matched text, such as whitespaces and comments.
+    Skip
+}
+
+impl<SymDef> Clone for LexerAction<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Token(class, value) => Self::Token(class.clone(), *value),
+            Self::Skip => Self::Skip
+        }
+    }
+}
+
+pub struct LexerRule<SymDef: SymbolDefinition> {
+    pub pattern: LexerPattern,
+    pub action: LexerAction<SymDef>
+}
+
+impl<SymDef> Clone for LexerRule<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { pattern: self.pattern.clone(), action: self.action.clone() }
+    }
+}
+
+/// The rules of a lexer
+///
+/// The longest match wins, and between matches of the same length, the first declared rule wins.
+pub struct LexerRuleSet<SymDef: SymbolDefinition> {
+    rules: Vec<LexerRule<SymDef>>,
+    eos: Option<TokenValueFunc<SymDef>>
+}
+
+impl<SymDef> Clone for LexerRuleSet<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { rules: self.rules.clone(), eos: self.eos }
+    }
+}
+
+impl<SymDef> Default for LexerRuleSet<SymDef> where SymDef: SymbolDefinition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<SymDef> LexerRuleSet<SymDef> where SymDef: SymbolDefinition {
+    pub fn new() -> Self {
+        Self { rules: vec![], eos: None }
+    }
+
+    /// Iterate over the rules
+    pub fn iter(&self) -> impl Iterator<Item=&LexerRule<SymDef>> {
+        self.rules.iter()
+    }
+
+    pub fn get(&self, rule_id: usize) -> Option<&LexerRule<SymDef>> {
+        self.rules.get(rule_id)
+    }
+
+    /// Produce a token of the class for the text matching the pattern.
+    pub fn token<F: Fn(&str) -> SymDef::Value + Sync + Send>(&mut self, class: SymDef::Class, pattern: &str, value: &'static F) -> &mut Self {
+        self.add(LexerPattern::Regex(pattern.to_string()), LexerAction::Token(class, value))
+    }
+
+    /// Produce a token of the class for the text, matched as is.
+    pub fn literal<F: Fn(&str) -> SymDef::Value + Sync + Send>(&mut self, class: SymDef::Class, text: &str, value: &'static F) -> &mut Self {
+        self.add(LexerPattern::Literal(text.to_string()), LexerAction::Token(class, value))
+    }
+
+    /// Discard the text matching the pattern.
+    pub fn skip(&mut self, pattern: &str) -> &mut Self {
+        self.add(LexerPattern::Regex(pattern.to_string()), LexerAction::Skip)
+    }
+
+    /// Build the value of the end of stream token, produced once the input is consumed.
+    pub fn eos<F: Fn(&str) -> SymDef::Value + Sync + Send>(&mut self, value: &'static F) -> &mut Self {
+        self.eos = Some(value);
+        self
+    }
+
+    /// Returns the builder of the end of stream value, if declared.
+    pub fn get_eos(&self) -> Option<TokenValueFunc<SymDef>> {
+        self.eos
+    }
+
+    fn add(&mut self, pattern: LexerPattern, action: LexerAction<SymDef>) -> &mut Self {
+        self.rules.push(LexerRule { pattern, action });
+        self
+    }
+}
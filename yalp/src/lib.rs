@@ -1,4 +1,14 @@
 pub mod symbol;
 pub mod lexer;
 pub mod parser;
-pub mod span;
\ No newline at end of file
+pub mod span;
+pub mod source;
+pub mod cst;
+
+#[cfg(feature = "macros")]
+pub use yalp_macro::grammar;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use lazy_static::lazy_static;
+}
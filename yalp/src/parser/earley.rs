@@ -0,0 +1,470 @@
+use std::{collections::{HashMap, HashSet}, convert::Infallible, rc::Rc};
+
+use crate::{span::Span, symbol::{traits::{Symbol, SymbolDefinition}, Sym}};
+
+use super::{rule::{analysis::GrammarAnalysis, ParserRule, ParserRuleSet}, traits::{Parser, ParserSymbolClass}, ParserError};
+
+/// Earley parser
+///
+/// It accepts any grammar, including left-recursive and ambiguous ones, at the expense of speed,
+/// and runs the same reducers as [super::lr::LrParser], so grammars can be prototyped with it.
+///
+/// The input is recognized first, then one derivation is reduced.
+/// If there are several, the rules declared first, and the shortest leftmost children, are preferred.
+///
+/// There is no error recovery, the first syntax error aborts the parsing.
+pub struct EarleyParser<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    analysis: GrammarAnalysis<SymDef>
+}
+
+impl<'a, SymDef, Ctx> Parser<'a> for EarleyParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    type Symbol = Sym<SymDef>;
+    type SymbolDefinition = SymDef;
+    type GenerationError = Infallible;
+    type Context = Ctx;
+
+    fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition, Self::Context>) -> Result<Self, Self::GenerationError> {
+        Ok(Self {
+            analysis: rules.analysis(),
+            rules
+        })
+    }
+
+    fn parse_with_context<V, Token, TokenStream, TokenError>(&self, stream: TokenStream, ctx: &mut Self::Context) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>,
+            ParserError: From<TokenError>,
+            Self::Symbol: From<Token>,
+            <Self::Symbol as SymbolDefinition>::Value: TryInto<V>
+    {
+        let Some(root) = self.rules.root() else {
+            return Err(vec![ParserError::corrupt_table(Span::from(0), "the grammar has no rule")]);
+        };
+        let eos = <SymDef::Class as ParserSymbolClass>::eos();
+
+        let mut chart = self.start(root);
+        let mut tokens: Vec<Option<Sym<SymDef>>> = vec![];
+
+        for tok in stream {
+            let tok: Sym<SymDef> = tok.map_err(|err| vec![err.into()])?.into();
+            let pos = tokens.len();
+            self.close(&mut chart, pos);
+
+            if *tok.get_type() == eos {
+                // As the LR and LL parsers, only the root rule accepts the input.
+                if !chart.sets[pos].iter().any(|item| item.origin == 0 && item.rule_id == root.id && self.is_complete(item)) {
+                    return Err(vec![ParserError::unexpected_token(tok, self.expected(&chart, pos))]);
+                }
+
+                let span = tok.span.clone();
+                tokens.push(Some(tok));
+
+                let tree = self
+                    .derive(root.id, 0, pos, &mut EarleyMemo::new(&chart, &tokens))
+                    .ok_or_else(|| vec![ParserError::corrupt_table(span, "the recognized input has no derivation")])?;
+                let sym = self.evaluate(&tree, &mut tokens, ctx).map_err(|err| vec![err])?;
+                return sym.into_value().map_err(|err| vec![err]);
+            }
+
+            // Scan the token
+            chart.new_set();
+            let scanned = chart.sets[pos]
+                .iter()
+                .filter(|item| self.next_symbol(item).as_ref() == Some(tok.get_type()))
+                .map(|item| EarleyItem { dot: item.dot + 1, ..item.clone() })
+                .collect::<Vec<_>>();
+
+            if scanned.is_empty() {
+                return Err(vec![ParserError::unexpected_token(tok, self.expected(&chart, pos))]);
+            }
+
+            scanned.into_iter().for_each(|item| chart.add(pos + 1, item));
+            tokens.push(Some(tok));
+        }
+
+        let span = tokens.last().and_then(|tok| tok.as_ref()).map(|tok| tok.span()).unwrap_or(Span::from(0));
+        Err(vec![ParserError::incomplete_input(span)])
+    }
+}
+
+impl<'a, SymDef, Ctx> EarleyParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Tells whether the sentence, not ended by the end of stream, belongs to the grammar, without running the reducers.
+    pub fn recognize(&self, sentence: &[SymDef::Class]) -> bool {
+        let Some(root) = self.rules.root() else {
+            return false;
+        };
+        let mut chart = self.start(root);
+
+        for (pos, class) in sentence.iter().enumerate() {
+            self.close(&mut chart, pos);
+            chart.new_set();
+
+            let scanned = chart.sets[pos]
+                .iter()
+                .filter(|item| self.next_symbol(item).as_ref() == Some(class))
+                .map(|item| EarleyItem { dot: item.dot + 1, ..item.clone() })
+                .collect::<Vec<_>>();
+
+            scanned.into_iter().for_each(|item| chart.add(pos + 1, item));
+        }
+
+        self.close(&mut chart, sentence.len());
+        chart.sets[sentence.len()]
+            .iter()
+            .any(|item| item.origin == 0 && item.rule_id == root.id && self.is_complete(item))
+    }
+
+    /// Returns the chart predicting the root rule.
+    fn start(&self, root: &ParserRule<SymDef>) -> EarleyChart {
+        let mut chart = EarleyChart::default();
+        chart.new_set();
+        chart.add(0, EarleyItem { rule_id: root.id, dot: 0, origin: 0 });
+        chart
+    }
+
+    /// Predict and complete the items of the set, until no new item is added.
+    ///
+    /// Predicting a nullable symbol also skips it, so that empty completions are not missed.
+    fn close(&self, chart: &mut EarleyChart, pos: usize) {
+        let mut i = 0;
+
+        while i < chart.sets[pos].len() {
+            let item = chart.sets[pos][i].clone();
+            i += 1;
+
+            match self.next_symbol(&item) {
+                // Predict
+                Some(sym) if !sym.is_terminal() => {
+                    for rule in self.rules.iter_by_lhs(sym.clone()) {
+                        chart.add(pos, EarleyItem { rule_id: rule.id, dot: 0, origin: pos });
+                    }
+
+                    if self.analysis.is_nullable(&sym) {
+                        chart.add(pos, EarleyItem { dot: item.dot + 1, ..item });
+                    }
+                },
+                // Complete
+                None => {
+                    let Some(lhs) = self.lhs(&item) else {
+                        continue;
+                    };
+                    let completed = chart.sets[item.origin]
+                        .iter()
+                        .filter(|parent| self.next_symbol(parent).as_ref() == Some(&lhs))
+                        .map(|parent| EarleyItem { dot: parent.dot + 1, ..parent.clone() })
+                        .collect::<Vec<_>>();
+
+                    completed.into_iter().for_each(|parent| chart.add(pos, parent));
+                },
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Returns the derivation of the rule over the tokens from start to end, following the completed items of the chart.
+    ///
+    /// The derivations being explored are skipped, to break the cycles of the grammar.
+    fn derive(&self, rule_id: usize, start: usize, end: usize, memo: &mut EarleyMemo<SymDef>) -> Option<Rc<EarleyNode>> {
+        if let Some(node) = memo.derivations.get(&(rule_id, start, end)) {
+            return node.clone();
+        }
+
+        if memo.exploring.contains(&(rule_id, start, end)) {
+            memo.cuts += 1;
+            return None;
+        }
+
+        let cuts = memo.cuts;
+        memo.exploring.push((rule_id, start, end));
+        let children = self.derive_sequence(rule_id, 0, start, end, memo);
+        memo.exploring.pop();
+
+        let node = children.map(|children| Rc::new(EarleyNode::Rule { rule_id, start, children }));
+        memo.remember((rule_id, start, end), node.clone(), cuts);
+        node
+    }
+
+    /// Returns the derivations of the symbols of the rule from the dot, covering the tokens from start to end.
+    ///
+    /// The rest of the symbols is derived before the current one, as it often fails sooner.
+    fn derive_sequence(&self, rule_id: usize, dot: usize, start: usize, end: usize, memo: &mut EarleyMemo<SymDef>) -> Option<Vec<Rc<EarleyNode>>> {
+        if self.rules.get(rule_id).is_some_and(|rule| rule.rhs.len() == dot) {
+            return (start == end).then(Vec::new);
+        }
+
+        if memo.failures.contains(&(rule_id, dot, start, end)) {
+            return None;
+        }
+
+        let cuts = memo.cuts;
+        let children = self.derive_symbol(rule_id, dot, start, end, memo);
+
+        if children.is_none() && memo.cuts == cuts {
+            memo.failures.insert((rule_id, dot, start, end));
+        }
+
+        children
+    }
+
+    /// Returns the derivations of the symbol at the dot of the rule, followed by the ones of the rest of the symbols.
+    fn derive_symbol(&self, rule_id: usize, dot: usize, start: usize, end: usize, memo: &mut EarleyMemo<SymDef>) -> Option<Vec<Rc<EarleyNode>>> {
+        let sym = self.rules.get(rule_id).and_then(|rule| rule.rhs.get(dot))?;
+
+        if sym.is_terminal() {
+            if start >= end || memo.tokens[start].as_ref().map(|tok| tok.get_type()) != Some(sym) {
+                return None;
+            }
+
+            let mut children = self.derive_sequence(rule_id, dot + 1, start + 1, end, memo)?;
+            children.insert(0, Rc::new(EarleyNode::Token(start)));
+            return Some(children);
+        }
+
+        for mid in start..=end {
+            let candidates = memo.chart.sets[mid]
+                .iter()
+                .filter(|item| item.origin == start && self.is_complete(item) && self.lhs(item).as_ref() == Some(sym))
+                .map(|item| item.rule_id)
+                .collect::<Vec<_>>();
+
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let Some(mut children) = self.derive_sequence(rule_id, dot + 1, mid, end, memo) else {
+                continue;
+            };
+
+            if let Some(node) = candidates.into_iter().find_map(|candidate| self.derive(candidate, start, mid, memo)) {
+                children.insert(0, node);
+                return Some(children);
+            }
+        }
+
+        None
+    }
+
+    /// Reduce the rules of the tree, bottom-up.
+    ///
+    /// The reduced symbol spans from its first to its last child,
+    /// an empty production gets the empty span right before the next token.
+    fn evaluate(&self, node: &EarleyNode, tokens: &mut [Option<Sym<SymDef>>], ctx: &mut Ctx) -> Result<Sym<SymDef>, ParserError> {
+        match node {
+            EarleyNode::Token(index) => tokens[*index]
+                .take()
+                .ok_or_else(|| ParserError::corrupt_table(Span::from(*index), format!("token {} already consumed", index))),
+            EarleyNode::Rule { rule_id, start, children } => {
+                let rule = self.rules
+                    .get(*rule_id)
+                    .ok_or_else(|| ParserError::corrupt_table(Span::from(*start), format!("missing rule {}", rule_id)))?;
+                let syms = children
+                    .iter()
+                    .map(|child| self.evaluate(child, tokens, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let span = match (syms.first(), syms.last()) {
+                    (Some(first), Some(last)) => first.span.join(&last.span),
+                    _ => tokens[*start].as_ref().map(|tok| tok.span.shrink_to_lo()).unwrap_or(Span::from(0))
+                };
+
+                Ok(Sym {
+                    span,
+                    class: rule.lhs.clone(),
+                    value: self.rules.execute(rule, syms, ctx)?
+                })
+            }
+        }
+    }
+
+    /// Returns the terminals which could have been scanned at the position.
+    fn expected(&self, chart: &EarleyChart, pos: usize) -> Vec<SymDef::Class> {
+        let mut expected = vec![];
+
+        for sym in chart.sets[pos].iter().filter_map(|item| self.next_symbol(item)) {
+            if sym.is_terminal() && !expected.contains(&sym) {
+                expected.push(sym);
+            }
+        }
+
+        expected
+    }
+
+    fn lhs(&self, item: &EarleyItem) -> Option<SymDef::Class> {
+        self.rules.get(item.rule_id).map(|rule| rule.lhs.clone())
+    }
+
+    fn next_symbol(&self, item: &EarleyItem) -> Option<SymDef::Class> {
+        self.rules.get(item.rule_id).and_then(|rule| rule.rhs.get(item.dot)).cloned()
+    }
+
+    fn is_complete(&self, item: &EarleyItem) -> bool {
+        self.rules.get(item.rule_id).is_some_and(|rule| rule.rhs.len() == item.dot)
+    }
+}
+
+/// A rule, the position of the cursor within its right-hand side, and the set it was predicted in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct EarleyItem {
+    rule_id: usize,
+    dot: usize,
+    origin: usize
+}
+
+/// The item sets, one per position within the input.
+#[derive(Default)]
+struct EarleyChart {
+    sets: Vec<Vec<EarleyItem>>,
+    seen: Vec<HashSet<EarleyItem>>
+}
+
+impl EarleyChart {
+    fn new_set(&mut self) {
+        self.sets.push(vec![]);
+        self.seen.push(HashSet::new());
+    }
+
+    /// Add the item to the set, unless it is already in it.
+    fn add(&mut self, pos: usize, item: EarleyItem) {
+        if self.seen[pos].insert(item.clone()) {
+            self.sets[pos].push(item);
+        }
+    }
+}
+
+/// A node of the derivation tree
+enum EarleyNode {
+    /// The token at the index of the input
+    Token(usize),
+    /// The rule, and the index of the first token it covers.
+    Rule { rule_id: usize, start: usize, children: Vec<Rc<EarleyNode>> }
+}
+
+/// The derivations found while deriving the recognized input, so each rule is derived once over the same tokens.
+///
+/// A failure is only remembered if no derivation being explored was skipped to find it,
+/// as it may succeed once the cycle is left.
+struct EarleyMemo<'c, SymDef: SymbolDefinition> {
+    chart: &'c EarleyChart,
+    tokens: &'c [Option<Sym<SymDef>>],
+    /// The derivations of the rules over the tokens from start to end, by (rule_id, start, end)
+    derivations: HashMap<(usize, usize, usize), Option<Rc<EarleyNode>>>,
+    /// The symbols of the rules failing to derive the tokens, by (rule_id, dot, start, end)
+    failures: HashSet<(usize, usize, usize, usize)>,
+    /// The derivations being explored, by (rule_id, start, end)
+    exploring: Vec<(usize, usize, usize)>,
+    /// Number of derivations skipped as they were being explored
+    cuts: usize
+}
+
+impl<'c, SymDef: SymbolDefinition> EarleyMemo<'c, SymDef> {
+    fn new(chart: &'c EarleyChart, tokens: &'c [Option<Sym<SymDef>>]) -> Self {
+        Self { chart, tokens, derivations: HashMap::new(), failures: HashSet::new(), exploring: vec![], cuts: 0 }
+    }
+
+    /// Remember the derivation, unless it failed after skipping a derivation being explored.
+    fn remember(&mut self, key: (usize, usize, usize), node: Option<Rc<EarleyNode>>, cuts: usize) {
+        if node.is_some() || self.cuts == cuts {
+            self.derivations.insert(key, node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::{parser::{fixtures::{first, sum, tokens, Class, SymDef}, lr::LrParser, traits::Parser, rule::ParserRuleSet, ParserErrorKind}, span::Span};
+
+    use super::EarleyParser;
+
+    lazy_static! {
+        // Ambiguous, and left-recursive
+        static ref RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add(Class::E, [Class::E, Class::Minus, Class::E], &|syms| Ok(syms[0].value - syms[2].value)) // E → E - E
+        .add(Class::E, [Class::List], &first) // E → List
+        .add(Class::List, [Class::List, Class::Num], &sum) // List → List num
+        .add(Class::List, [] as [Class; 0], &|_| Ok(0)) // List → ε
+        .to_owned();
+
+        // Right-recursive, with a cycle
+        static ref CYCLIC_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add(Class::E, [Class::E], &first) // E → E
+        .add(Class::E, [Class::Num, Class::Plus, Class::E], &sum) // E → num + E
+        .add(Class::E, [Class::Num], &first) // E → num
+        .to_owned();
+
+        // The root non-terminal has several rules, only the first one is the root.
+        static ref ROOT_ALTERNATIVE_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::List, [Class::List, Class::Plus, Class::Num], &sum) // List → List + num
+        .add(Class::List, [Class::Num], &first) // List → num
+        .to_owned();
+    }
+
+    #[test]
+    fn parse_any_grammar() {
+        let parser = EarleyParser::generate(&RULES).unwrap();
+
+        let value: i64 = parser.parse(tokens(&[(Class::Num, 1), (Class::Num, 2), (Class::Minus, 0), (Class::Num, 3)])).unwrap();
+        assert_eq!(value, 0);
+
+        // 5 - (ε - 3), rather than (5 - ε) - 3
+        let value: i64 = parser.parse(tokens(&[(Class::Num, 5), (Class::Minus, 0), (Class::Minus, 0), (Class::Num, 3)])).unwrap();
+        assert_eq!(value, 8);
+
+        let value: i64 = parser.parse(tokens(&[])).unwrap();
+        assert_eq!(value, 0);
+
+        let parser = EarleyParser::generate(&CYCLIC_RULES).unwrap();
+        let value: i64 = parser.parse(tokens(&[(Class::Num, 1), (Class::Plus, 0), (Class::Num, 2), (Class::Plus, 0), (Class::Num, 3)])).unwrap();
+        assert_eq!(value, 6);
+    }
+
+    #[test]
+    fn derive_long_inputs() {
+        let parser = EarleyParser::generate(&RULES).unwrap();
+
+        // Each subtraction can be split in many ways, which are only explored once.
+        let input = (0..40).flat_map(|_| [(Class::Minus, 0), (Class::Num, 1), (Class::Num, 1)]).collect::<Vec<_>>();
+        let value: i64 = parser.parse(tokens(&input)).unwrap();
+        assert_eq!(value, 0);
+    }
+
+    #[test]
+    fn report_syntax_errors() {
+        let parser = EarleyParser::generate(&CYCLIC_RULES).unwrap();
+
+        let errors = parser.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1), (Class::Plus, 0)])).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].span, Span::Interval { from: 2, to: 2 }));
+
+        let errors = parser.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1), (Class::Num, 2)])).unwrap_err();
+        assert!(matches!(errors[0].span, Span::Interval { from: 1, to: 2 }));
+    }
+
+    #[test]
+    fn accept_the_root_rule_only() {
+        let earley = EarleyParser::generate(&ROOT_ALTERNATIVE_RULES).unwrap();
+        let lr = LrParser::generate(&ROOT_ALTERNATIVE_RULES).unwrap();
+
+        // num is derived by List → num, but not by the root rule.
+        assert!(lr.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1)])).is_err());
+        assert!(earley.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1)])).is_err());
+        assert!(!earley.recognize(&[Class::Num]));
+
+        let input = [(Class::Num, 1), (Class::Plus, 0), (Class::Num, 2), (Class::Plus, 0), (Class::Num, 3)];
+        assert_eq!(earley.parse::<i64, _, _, _>(tokens(&input)).unwrap(), lr.parse::<i64, _, _, _>(tokens(&input)).unwrap());
+        assert!(earley.recognize(&[Class::Num, Class::Plus, Class::Num]));
+    }
+
+    #[test]
+    fn reject_an_empty_grammar() {
+        let rules = ParserRuleSet::<SymDef>::new();
+        let parser = EarleyParser::generate(&rules).unwrap();
+
+        let errors = parser.parse::<i64, _, _, _>(tokens(&[])).unwrap_err();
+        assert_eq!(errors[0].kind, ParserErrorKind::CorruptTable);
+        assert!(!parser.recognize(&[]));
+    }
+}
@@ -0,0 +1,80 @@
+//! The symbols, tokens and rules shared by the tests of the parsers.
+
+use crate::{parser::{rule::ParserRuleSet, traits::ParserSymbolClass, ParserError}, symbol::{traits::SymbolDefinition, Sym}};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Class {
+    Root,
+    S,
+    E,
+    T,
+    A,
+    B,
+    List,
+    Tail,
+    Num,
+    Plus,
+    Minus,
+    LeftParen,
+    RightParen,
+    Letter,
+    X,
+    Y,
+    Z,
+    Error,
+    EOS
+}
+
+impl ParserSymbolClass for Class {
+    fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Class::Num | Class::Plus | Class::Minus | Class::LeftParen | Class::RightParen
+            | Class::Letter | Class::X | Class::Y | Class::Z | Class::Error | Class::EOS
+        )
+    }
+
+    fn eos() -> Self {
+        Self::EOS
+    }
+}
+
+pub struct SymDef;
+
+impl SymbolDefinition for SymDef {
+    type Class = Class;
+    type Value = i64;
+}
+
+/// The i-th token of the input.
+pub fn tok(i: usize, class: Class, value: i64) -> Sym<SymDef> {
+    Sym::new(i..i + 1, class, value)
+}
+
+/// The tokens of the input, ended by the end of stream.
+pub fn tokens(input: &[(Class, i64)]) -> impl Iterator<Item=Result<Sym<SymDef>, ParserError>> + '_ {
+    input
+    .iter()
+    .enumerate()
+    .map(|(i, (class, value))| Ok(tok(i, class.clone(), *value)))
+    .chain(std::iter::once(Ok(Sym::new(input.len(), Class::EOS, 0))))
+}
+
+/// Reduce to the value of the first symbol.
+pub fn first(mut syms: Vec<Sym<SymDef>>) -> Result<i64, ParserError> {
+    Ok(syms.remove(0).value)
+}
+
+/// Reduce to the sum of the values of the symbols, the operators being valued 0.
+pub fn sum(syms: Vec<Sym<SymDef>>) -> Result<i64, ParserError> {
+    Ok(syms.iter().map(|sym| sym.value).sum())
+}
+
+/// The additions of numbers, left-recursive.
+pub fn expr_rules<Ctx>() -> ParserRuleSet<SymDef, Ctx> {
+    ParserRuleSet::new()
+    .add(Class::S, [Class::E], &first) // S → E
+    .add(Class::E, [Class::E, Class::Plus, Class::Num], &sum) // E → E + num
+    .add(Class::E, [Class::Num], &first) // E → num
+    .to_owned()
+}
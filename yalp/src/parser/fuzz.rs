@@ -0,0 +1,290 @@
+//! Random sentences of a grammar, to fuzz its parsers.
+//!
+//! The sentences are derived from the root rule up to a depth bound, then mutated to get, most likely, invalid ones.
+//! The [Earley parser](super::earley::EarleyParser) tells whether a sentence belongs to the grammar,
+//! the parser under test must then agree with it, and never panic.
+use std::{fmt::Display, panic::{catch_unwind, AssertUnwindSafe}};
+
+use crate::symbol::{traits::SymbolDefinition, Sym};
+
+use super::{earley::EarleyParser, rule::ParserRuleSet, traits::{Parser, ParserSymbolClass}, ParserError};
+
+/// Generate random sentences, as sequences of terminals, from the rules.
+///
+/// The rules containing the error symbol are never derived, and the sentences are not ended by the end of stream.
+pub struct SentenceGenerator<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition + 'static {
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    /// The minimal depth of a derivation of each rule, none if it derives no sentence.
+    heights: Vec<Option<usize>>,
+    terminals: Vec<SymDef::Class>,
+    max_depth: usize,
+    rng: Rng
+}
+
+impl<'a, SymDef, Ctx> SentenceGenerator<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    pub fn new(rules: &'a ParserRuleSet<SymDef, Ctx>, seed: u64) -> Self {
+        let eos = <SymDef::Class as ParserSymbolClass>::eos();
+        let error = rules.get_error_symbol().map(|(class, _)| class.clone());
+
+        let terminals = rules
+            .symbols()
+            .into_iter()
+            .filter(|sym| sym.is_terminal() && *sym != eos && Some(sym) != error.as_ref())
+            .collect();
+
+        let mut heights: Vec<Option<usize>> = vec![None; rules.iter().count()];
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for rule in rules.iter().filter(|rule| !rule.rhs.iter().any(|sym| Some(sym) == error.as_ref())) {
+                let height = rule.rhs
+                    .iter()
+                    .filter(|sym| !sym.is_terminal())
+                    .map(|sym| Self::symbol_height(rules, &heights, sym))
+                    .try_fold(0, |max, height| height.map(|height| max.max(height)))
+                    .map(|height| height + 1);
+
+                if height.is_some() && (heights[rule.id].is_none() || height < heights[rule.id]) {
+                    heights[rule.id] = height;
+                    changed = true;
+                }
+            }
+        }
+
+        Self { rules, heights, terminals, max_depth: 8, rng: Rng::new(seed) }
+    }
+
+    /// Set the depth past which the shortest derivations are chosen, 8 by default.
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Returns a random sentence of the grammar.
+    pub fn sentence(&mut self) -> Vec<SymDef::Class> {
+        let mut sentence = vec![];
+
+        if let Some(root) = self.rules.root() {
+            root.rhs.iter().for_each(|sym| self.derive(sym, 1, &mut sentence));
+        }
+
+        sentence
+    }
+
+    /// Returns a random sentence, with a token removed, duplicated, replaced or swapped with the next one.
+    pub fn mutated(&mut self) -> Vec<SymDef::Class> {
+        let mut sentence = self.sentence();
+
+        if sentence.is_empty() || self.terminals.is_empty() {
+            if let Some(term) = self.random_terminal() {
+                sentence.push(term);
+            }
+            return sentence;
+        }
+
+        let pos = self.rng.below(sentence.len());
+
+        match self.rng.below(4) {
+            0 => { sentence.remove(pos); },
+            1 => {
+                let tok = sentence[pos].clone();
+                sentence.insert(pos, tok);
+            },
+            2 => sentence[pos] = self.random_terminal().unwrap(),
+            _ => {
+                let next = (pos + 1).min(sentence.len() - 1);
+                sentence.swap(pos, next);
+            }
+        }
+
+        sentence
+    }
+
+    fn derive(&mut self, sym: &SymDef::Class, depth: usize, sentence: &mut Vec<SymDef::Class>) {
+        if sym.is_terminal() {
+            sentence.push(sym.clone());
+            return;
+        }
+
+        let candidates = self.rules
+            .iter_by_lhs(sym.clone())
+            .filter_map(|rule| self.heights[rule.id].map(|height| (rule, height)))
+            .collect::<Vec<_>>();
+
+        // Past the depth bound, only the shortest derivations are chosen, so that the sentence ends.
+        let candidates = match candidates.iter().map(|(_, height)| *height).min() {
+            Some(min) if depth >= self.max_depth => candidates.into_iter().filter(|(_, height)| *height == min).collect(),
+            _ => candidates
+        };
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        let (rule, _) = candidates[self.rng.below(candidates.len())];
+        rule.rhs.iter().for_each(|sym| self.derive(sym, depth + 1, sentence));
+    }
+
+    fn random_terminal(&mut self) -> Option<SymDef::Class> {
+        (!self.terminals.is_empty()).then(|| self.terminals[self.rng.below(self.terminals.len())].clone())
+    }
+
+    fn symbol_height(rules: &ParserRuleSet<SymDef, Ctx>, heights: &[Option<usize>], sym: &SymDef::Class) -> Option<usize> {
+        rules
+            .iter_by_lhs(sym.clone())
+            .filter_map(|rule| heights[rule.id])
+            .min()
+    }
+}
+
+/// A sentence on which the parser under test misbehaved
+pub struct FuzzFailure<SymDef: SymbolDefinition> {
+    pub sentence: Vec<SymDef::Class>,
+    pub kind: FuzzFailureKind
+}
+
+#[derive(Debug)]
+pub enum FuzzFailureKind {
+    /// The sentence belongs to the grammar, but the parser rejected it.
+    Rejected(Vec<ParserError>),
+    /// The sentence does not belong to the grammar, but the parser accepted it.
+    Accepted,
+    /// The parser panicked, with the given message.
+    Panicked(String)
+}
+
+impl<SymDef> Display for FuzzFailure<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            FuzzFailureKind::Rejected(errors) => write!(f, "{:?} was rejected: {:?}", self.sentence, errors),
+            FuzzFailureKind::Accepted => write!(f, "{:?} was accepted", self.sentence),
+            FuzzFailureKind::Panicked(message) => write!(f, "{:?} panicked: {}", self.sentence, message)
+        }
+    }
+}
+
+/// Feed the parser with valid and mutated sentences, returns the ones it misbehaved on.
+///
+/// The values of the tokens are built from their class, and each sentence is parsed within a default context.
+/// The sentences rejected because of a %nonassoc declaration are reported as well.
+pub fn fuzz<'a, P, SymDef, Ctx, F>(parser: &P, generator: &mut SentenceGenerator<'a, SymDef, Ctx>, iterations: usize, value: F) -> Vec<FuzzFailure<SymDef>>
+where P: Parser<'a, Symbol = Sym<SymDef>, SymbolDefinition = SymDef, Context = Ctx>,
+        Ctx: Default,
+        SymDef: SymbolDefinition,
+        SymDef::Class: ParserSymbolClass,
+        F: Fn(&SymDef::Class) -> SymDef::Value
+{
+    let Ok(oracle) = EarleyParser::<'_, SymDef, Ctx>::generate(generator.rules);
+    let mut failures = vec![];
+
+    for i in 0..iterations {
+        let sentence = if i % 2 == 0 { generator.sentence() } else { generator.mutated() };
+
+        let tokens = sentence
+            .iter()
+            .cloned()
+            .chain(std::iter::once(<SymDef::Class as ParserSymbolClass>::eos()))
+            .enumerate()
+            .map(|(i, class)| Ok::<_, ParserError>(Sym::new(i..i + 1, class.clone(), value(&class))));
+
+        let valid = oracle.recognize(&sentence);
+
+        let kind = match catch_unwind(AssertUnwindSafe(|| parser.parse::<SymDef::Value, _, _, _>(tokens))) {
+            Ok(Ok(_)) if !valid => Some(FuzzFailureKind::Accepted),
+            Ok(Err(errors)) if valid => Some(FuzzFailureKind::Rejected(errors)),
+            Ok(_) => None,
+            Err(panic) => Some(FuzzFailureKind::Panicked(
+                panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|msg| msg.to_string()))
+                .unwrap_or_default()
+            ))
+        };
+
+        if let Some(kind) = kind {
+            failures.push(FuzzFailure { sentence, kind });
+        }
+    }
+
+    failures
+}
+
+/// xorshift64* pseudo-random generator, so that the sentences are reproducible from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Self(seed ^ 0x9e3779b97f4a7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// Returns a number in 0..n
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::parser::{fixtures::{first, sum, Class, SymDef}, lr::LrParser, rule::ParserRuleSet, traits::Parser};
+
+    use super::{fuzz, SentenceGenerator};
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::List], &first) // S → List
+        .add(Class::List, [Class::List, Class::E], &sum) // List → List E
+        .add(Class::List, [] as [Class; 0], &|_| Ok(0)) // List → ε
+        .add(Class::E, [Class::E, Class::Plus, Class::Num], &sum) // E → E + num
+        .add(Class::E, [Class::LeftParen, Class::E, Class::RightParen], &|syms| Ok(syms[1].value)) // E → ( E )
+        .add(Class::E, [Class::Num], &first) // E → num
+        .to_owned();
+
+        // The root non-terminal has several rules, the sentences must be derived from the first one.
+        static ref ROOT_ALTERNATIVE_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::List, [Class::List, Class::Plus, Class::Num], &sum) // List → List + num
+        .add(Class::List, [Class::LeftParen, Class::List, Class::RightParen], &|syms| Ok(syms[1].value)) // List → ( List )
+        .add(Class::List, [Class::Num], &first) // List → num
+        .to_owned();
+    }
+
+    #[test]
+    fn generate_bounded_sentences() {
+        let mut generator = SentenceGenerator::new(&RULES, 42);
+        generator.max_depth(4);
+
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        for _ in 0..100 {
+            let sentence = generator.sentence();
+            let depth = sentence.iter().filter(|class| **class == Class::LeftParen).count();
+
+            assert!(depth <= 4, "{:?}", sentence);
+            assert_eq!(sentence.iter().filter(|class| **class == Class::RightParen).count(), depth);
+        }
+
+        let failures = fuzz(&parser, &mut generator, 500, |_| 1);
+        assert!(failures.is_empty(), "{}", failures.iter().map(|failure| failure.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    fn fuzz_a_root_with_alternatives() {
+        let mut generator = SentenceGenerator::new(&ROOT_ALTERNATIVE_RULES, 7);
+        let parser = LrParser::generate(&ROOT_ALTERNATIVE_RULES).unwrap();
+
+        let failures = fuzz(&parser, &mut generator, 500, |_| 1);
+        assert!(failures.is_empty(), "{}", failures.iter().map(|failure| failure.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use crate::{parser::{rule::ParserRule, traits::ParserSymbolClass}, symbol::traits::SymbolDefinition};
+
+use super::LlParser;
+
+impl<'a, SymDef, Ctx> LlParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Generate the recursive-descent functions of the grammar, as Rust source code.
+    ///
+    /// A `parse_<non-terminal>` function is generated for each non-terminal, and a `parse` function deriving the root rule,
+    /// they receive the rule set to run its reducers, and the context of the reducers. The symbol definition and class are given by their paths,
+    /// the classes being variants named after their [Debug] representation, as generated by [crate::grammar].
+    ///
+    /// ```ignore
+    /// // build.rs
+    /// std::fs::write(out_dir.join("descent.rs"), parser.to_rust_source("crate::ExprDef", "crate::Class"))?;
+    /// // src/lib.rs
+    /// mod descent { include!(concat!(env!("OUT_DIR"), "/descent.rs")); }
+    /// let value = descent::parse(&RULES, tokens, &mut ())?.into_value()?;
+    /// ```
+    pub fn to_rust_source(&self, def: &str, class: &str) -> String {
+        let mut src = String::new();
+        let sym = format!("::yalp::symbol::Sym<{}>", def);
+        let result = format!("::std::result::Result<{}, ::yalp::parser::ParserError>", sym);
+        let bound = format!("I: ::std::iter::Iterator<Item = {}>", result);
+
+        writeln!(src, "// Generated by `LlParser::to_rust_source`, do not edit.").unwrap();
+
+        if let Some(root) = self.rules.root() {
+            writeln!(src).unwrap();
+            writeln!(src, "/// Parse the tokens, from the root rule.").unwrap();
+            writeln!(src, "pub fn parse<I, Ctx>(rules: &::yalp::parser::rule::ParserRuleSet<{}, Ctx>, tokens: I, ctx: &mut Ctx) -> {}", def, result).unwrap();
+            writeln!(src, "where {}", bound).unwrap();
+            writeln!(src, "{{").unwrap();
+            writeln!(src, "    let mut cursor = ::yalp::parser::ll::LlCursor::new(rules, tokens, ctx);").unwrap();
+            writeln!(src, "    let cursor = &mut cursor;").unwrap();
+            writeln!(src, "    // {}", format_rule(root)).unwrap();
+            writeln!(src, "    let root = {}?;", self.derive(root, class)).unwrap();
+            writeln!(src, "    cursor.finish(root)").unwrap();
+            writeln!(src, "}}").unwrap();
+        }
+
+        for lhs in self.rules.symbols().into_iter().filter(|sym| !sym.is_terminal()) {
+            writeln!(src).unwrap();
+            writeln!(src, "/// Derive {:?}", lhs).unwrap();
+            writeln!(src, "pub fn {}<I, Ctx>(cursor: &mut ::yalp::parser::ll::LlCursor<'_, {}, I, Ctx>) -> {}", function_name(&lhs), def, result).unwrap();
+            writeln!(src, "where {}", bound).unwrap();
+            writeln!(src, "{{").unwrap();
+            writeln!(src, "    match cursor.peek_class()? {{").unwrap();
+
+            let predictions = self.table.iter_predictions(&lhs).collect::<Vec<_>>();
+
+            for rule in self.rules.iter_by_lhs(lhs.clone()) {
+                let lookaheads = predictions
+                    .iter()
+                    .filter(|(_, rule_id)| *rule_id == rule.id)
+                    .map(|(terminal, _)| format!("{}::{:?}", class, terminal))
+                    .collect::<Vec<_>>();
+
+                if lookaheads.is_empty() {
+                    continue;
+                }
+
+                writeln!(src, "        // {}", format_rule(rule)).unwrap();
+                writeln!(src, "        {} => {},", lookaheads.join(" | "), self.derive(rule, class)).unwrap();
+            }
+
+            let expected = predictions.iter().map(|(terminal, _)| format!("{}::{:?}", class, terminal)).join(", ");
+            writeln!(src, "        _ => ::std::result::Result::Err(cursor.unexpected(::std::vec![{}]))", expected).unwrap();
+            writeln!(src, "    }}").unwrap();
+            writeln!(src, "}}").unwrap();
+        }
+
+        src
+    }
+
+    /// Returns the expression deriving the symbols of the rule from the cursor, then reducing them.
+    fn derive(&self, rule: &ParserRule<SymDef>, class: &str) -> String {
+        let syms = rule.rhs
+            .iter()
+            .map(|sym| match sym.is_terminal() {
+                true => format!("cursor.expect({}::{:?})?", class, sym),
+                false => format!("{}(cursor)?", function_name(sym))
+            })
+            .join(", ");
+
+        // The symbols are derived before the cursor is borrowed to reduce them.
+        format!("{{ let syms = ::std::vec![{}]; cursor.reduce({}, syms) }}", syms, rule.id)
+    }
+}
+
+/// Render the rule as "lhs → rhs", or "lhs → ε" for an empty production.
+fn format_rule<SymDef: SymbolDefinition>(rule: &ParserRule<SymDef>) -> String {
+    match rule.rhs.is_empty() {
+        true => format!("{:?} → ε", rule.lhs),
+        false => format!("{:?} → {}", rule.lhs, rule.rhs.iter().map(|sym| format!("{:?}", sym)).join(" "))
+    }
+}
+
+/// Returns the name of the function deriving the non-terminal, "parse_" followed by its name in snake case.
+fn function_name<C: std::fmt::Debug>(class: &C) -> String {
+    let mut name = "parse".to_string();
+
+    for c in format!("{:?}", class).chars() {
+        if c.is_uppercase() || name == "parse" {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+
+    name
+}
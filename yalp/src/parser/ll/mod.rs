@@ -0,0 +1,239 @@
+use crate::{span::Span, symbol::{traits::{Symbol, SymbolDefinition}, Sym}};
+
+use self::table::{LlParserTable, LlParserTableError};
+use super::{rule::ParserRuleSet, traits::{Parser, ParserSymbolClass}, ParserError};
+
+mod descent;
+pub mod table;
+
+/// LL(1) parser
+///
+/// It predicts the rule to derive each non-terminal by from the lookahead, which is faster than an LR parser,
+/// but only accepts LL(1) grammars, see [LlParserTable::generate].
+/// It runs the same reducers as [super::lr::LrParser], and can be turned into recursive-descent functions, see [LlParser::to_rust_source].
+///
+/// There is no error recovery, the first syntax error aborts the parsing.
+pub struct LlParser<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    pub table: LlParserTable<SymDef>
+}
+
+/// What is left to parse, the symbols are derived from left to right.
+enum LlStep<SymDef: SymbolDefinition> {
+    Derive(SymDef::Class),
+    Reduce(usize)
+}
+
+impl<'a, SymDef, Ctx> Parser<'a> for LlParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    type Symbol = Sym<SymDef>;
+    type SymbolDefinition = SymDef;
+    type GenerationError = LlParserTableError<SymDef>;
+    type Context = Ctx;
+
+    fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition, Self::Context>) -> Result<Self, Self::GenerationError> {
+        Ok(Self {
+            table: LlParserTable::generate(rules)?,
+            rules
+        })
+    }
+
+    fn parse_with_context<V, Token, TokenStream, TokenError>(&self, stream: TokenStream, ctx: &mut Self::Context) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>,
+            ParserError: From<TokenError>,
+            Self::Symbol: From<Token>,
+            <Self::Symbol as SymbolDefinition>::Value: TryInto<V>
+    {
+        let mut cursor = LlCursor::new(self.rules, stream.map(|tok| tok.map(Sym::<SymDef>::from).map_err(ParserError::from)), ctx);
+
+        let Some(root) = self.rules.root() else {
+            return Err(vec![ParserError::corrupt_table(Span::from(0), "the grammar has no rule")]);
+        };
+
+        // The root rule is derived whatever the lookahead, as it is the only one the parser starts from.
+        let mut steps: Vec<LlStep<SymDef>> = vec![LlStep::Reduce(root.id)];
+        steps.extend(root.rhs.iter().rev().cloned().map(LlStep::Derive));
+
+        let mut syms: Vec<Sym<SymDef>> = vec![];
+
+        while let Some(step) = steps.pop() {
+            match step {
+                LlStep::Derive(class) if class.is_terminal() => {
+                    syms.push(cursor.expect(class).map_err(|err| vec![err])?);
+                },
+                LlStep::Derive(class) => {
+                    let lookahead = cursor.peek_class().map_err(|err| vec![err])?;
+
+                    let Some(rule) = self.table.predict(&class, &lookahead).and_then(|rule_id| self.rules.get(rule_id)) else {
+                        let expected = self.table.iter_predictions(&class).map(|(terminal, _)| terminal.clone()).collect();
+                        return Err(vec![cursor.unexpected(expected)]);
+                    };
+
+                    steps.push(LlStep::Reduce(rule.id));
+                    steps.extend(rule.rhs.iter().rev().cloned().map(LlStep::Derive));
+                },
+                LlStep::Reduce(rule_id) => {
+                    let len = self.rules.get(rule_id).map(|rule| rule.rhs.len()).unwrap_or_default();
+                    let children = syms.split_off(syms.len().saturating_sub(len));
+                    syms.push(cursor.reduce(rule_id, children).map_err(|err| vec![err])?);
+                }
+            }
+        }
+
+        let Some(root) = syms.pop() else {
+            return Err(vec![ParserError::incomplete_input(Span::from(0))]);
+        };
+
+        cursor
+            .finish(root)
+            .and_then(|sym| sym.into_value())
+            .map_err(|err| vec![err])
+    }
+}
+
+/// The tokens left to parse, the rules to reduce them by, and the context of their reducers.
+///
+/// It drives the [LlParser], and the recursive-descent functions generated by [LlParser::to_rust_source].
+pub struct LlCursor<'a, SymDef, Tokens, Ctx = ()> where SymDef: SymbolDefinition {
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    ctx: &'a mut Ctx,
+    tokens: Tokens,
+    lookahead: Option<Sym<SymDef>>,
+    /// The span of the last token consumed
+    last: Span
+}
+
+impl<'a, SymDef, Tokens, Ctx> LlCursor<'a, SymDef, Tokens, Ctx>
+where SymDef: SymbolDefinition,
+        SymDef::Class: ParserSymbolClass,
+        Tokens: Iterator<Item = Result<Sym<SymDef>, ParserError>>
+{
+    pub fn new(rules: &'a ParserRuleSet<SymDef, Ctx>, tokens: Tokens, ctx: &'a mut Ctx) -> Self {
+        Self { rules, ctx, tokens, lookahead: None, last: Span::from(0) }
+    }
+
+    /// Returns the next token, without consuming it.
+    pub fn peek(&mut self) -> Result<&Sym<SymDef>, ParserError> {
+        let tok = match self.lookahead.take() {
+            Some(tok) => tok,
+            None => self.tokens.next().ok_or_else(|| ParserError::unexpected_eof(self.last.shrink_to_hi()))??
+        };
+
+        Ok(self.lookahead.insert(tok))
+    }
+
+    /// Returns the class of the next token, without consuming it.
+    pub fn peek_class(&mut self) -> Result<SymDef::Class, ParserError> {
+        self.peek().map(|tok| tok.class.clone())
+    }
+
+    /// Consume the next token, if it is of the class.
+    pub fn expect(&mut self, class: SymDef::Class) -> Result<Sym<SymDef>, ParserError> {
+        if self.peek()?.class != class {
+            return Err(self.unexpected(vec![class]));
+        }
+
+        let tok = self.lookahead.take().ok_or_else(|| ParserError::unexpected_eof(self.last.shrink_to_hi()))?;
+        self.last = tok.span();
+        Ok(tok)
+    }
+
+    /// The next token is none of the expected ones.
+    pub fn unexpected(&mut self, mut expected: Vec<SymDef::Class>) -> ParserError {
+        // The error symbol is never produced by the lexer.
+        if let Some((error, _)) = self.rules.get_error_symbol() {
+            expected.retain(|class| class != error);
+        }
+
+        match self.lookahead.clone() {
+            Some(tok) => ParserError::unexpected_token(tok, expected),
+            None => ParserError::unexpected_eof(self.last.shrink_to_hi())
+        }
+    }
+
+    /// Execute the reducer of the rule, within the context.
+    ///
+    /// The reduced symbol spans from its first to its last child,
+    /// an empty production gets the empty span right before the lookahead, as for [super::lr::LrParser].
+    pub fn reduce(&mut self, rule_id: usize, syms: Vec<Sym<SymDef>>) -> Result<Sym<SymDef>, ParserError> {
+        let cursor = self.lookahead.as_ref().map(|tok| tok.span()).unwrap_or_else(|| self.last.shrink_to_hi());
+
+        let rule = self.rules
+            .get(rule_id)
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), format!("missing rule {}", rule_id)))?;
+
+        let span = match (syms.first(), syms.last()) {
+            (Some(first), Some(last)) => first.span.join(&last.span),
+            _ => cursor.shrink_to_lo()
+        };
+
+        Ok(Sym {
+            span,
+            class: rule.lhs.clone(),
+            value: self.rules.execute(rule, syms, self.ctx)?
+        })
+    }
+
+    /// Returns the root symbol, if the next token ends the stream.
+    pub fn finish(&mut self, root: Sym<SymDef>) -> Result<Sym<SymDef>, ParserError> {
+        let eos = <SymDef::Class as ParserSymbolClass>::eos();
+
+        if self.peek()?.class != eos {
+            return Err(self.unexpected(vec![eos]));
+        }
+
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::parser::{fixtures::{expr_rules, first, sum, tokens, Class, SymDef}, lr::LrParser, rule::ParserRuleSet, traits::Parser, ParserErrorKind};
+
+    use super::LlParser;
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add(Class::E, [Class::Num, Class::Tail], &sum) // E → num Tail
+        .add(Class::Tail, [Class::Plus, Class::Num, Class::Tail], &sum) // Tail → + num Tail
+        .add(Class::Tail, [] as [Class; 0], &|_| Ok(0)) // Tail → ε
+        .to_owned();
+
+        static ref LEFT_RECURSIVE: ParserRuleSet<SymDef> = expr_rules();
+    }
+
+    #[test]
+    fn parse_as_the_lr_parser() {
+        let ll = LlParser::generate(&RULES).unwrap();
+        let lr = LrParser::generate(&RULES).unwrap();
+        let input = [(Class::Num, 1), (Class::Plus, 0), (Class::Num, 2), (Class::Plus, 0), (Class::Num, 3)];
+
+        assert_eq!(ll.parse::<i64, _, _, _>(tokens(&input)).unwrap(), 6);
+        assert_eq!(ll.parse::<i64, _, _, _>(tokens(&input)).unwrap(), lr.parse::<i64, _, _, _>(tokens(&input)).unwrap());
+
+        let errors = ll.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1), (Class::Num, 2)])).unwrap_err();
+        assert_eq!(errors[0].message, "expected Plus or EOS, found Num");
+
+        let errors = ll.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1), (Class::Plus, 0)]).take(2)).unwrap_err();
+        assert_eq!(errors[0].kind, ParserErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn suggest_removing_left_recursion() {
+        let Err(error) = LlParser::generate(&LEFT_RECURSIVE) else {
+            panic!("the grammar is left-recursive");
+        };
+
+        assert_eq!(error.left_recursions.len(), 1);
+        assert_eq!(error.left_recursions[0].suggestion, vec![
+            "E → Num E'".to_string(),
+            "E' → Plus Num E'".to_string(),
+            "E' → ε".to_string()
+        ]);
+
+        assert_eq!(error.conflicts.len(), 1);
+        assert_eq!(error.conflicts[0].rules, vec![1, 2]);
+    }
+}
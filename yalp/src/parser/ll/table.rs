@@ -0,0 +1,287 @@
+use std::fmt::{Debug, Display};
+
+use itertools::Itertools;
+
+use crate::{parser::{rule::{analysis::GrammarAnalysis, ParserRuleSet}, traits::ParserSymbolClass}, symbol::traits::SymbolDefinition};
+
+/// The predictive table, the rule to derive a non-terminal by, depending on the lookahead.
+pub struct LlParserTable<SymDef: SymbolDefinition> {
+    /// A row per non-terminal, with the rule predicted by each lookahead
+    rows: Vec<LlParserRow<SymDef>>
+}
+
+impl<SymDef> Clone for LlParserTable<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { rows: self.rows.clone() }
+    }
+}
+
+impl<SymDef> Debug for LlParserTable<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LlParserTable").field(&self.rows).finish()
+    }
+}
+
+/// The rules of a non-terminal predicted by each lookahead, as (lookahead, rule)
+struct LlParserRow<SymDef: SymbolDefinition> {
+    lhs: SymDef::Class,
+    predictions: Vec<(SymDef::Class, usize)>
+}
+
+impl<SymDef> Clone for LlParserRow<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { lhs: self.lhs.clone(), predictions: self.predictions.clone() }
+    }
+}
+
+impl<SymDef> Debug for LlParserRow<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlParserRow").field("lhs", &self.lhs).field("predictions", &self.predictions).finish()
+    }
+}
+
+impl<SymDef> LlParserTable<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Generate the LL(1) table
+    ///
+    /// Fails if a lookahead predicts more than one rule of a non-terminal, or if a non-terminal is left-recursive.
+    pub fn generate<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> Result<Self, LlParserTableError<SymDef>> {
+        let analysis = rules.analysis();
+        let mut rows: Vec<LlParserRow<SymDef>> = vec![];
+        let mut conflicts: Vec<LlConflict<SymDef>> = vec![];
+
+        for rule in rules.iter() {
+            let row = match rows.iter().position(|row| row.lhs == rule.lhs) {
+                Some(index) => index,
+                None => {
+                    rows.push(LlParserRow { lhs: rule.lhs.clone(), predictions: vec![] });
+                    rows.len() - 1
+                }
+            };
+
+            for terminal in analysis.first_of(&rule.rhs, &analysis.follow(&rule.lhs)) {
+                let predicted = rows[row].predictions
+                    .iter()
+                    .find(|(lookahead, _)| *lookahead == terminal)
+                    .map(|(_, rule_id)| *rule_id);
+
+                let Some(predicted) = predicted else {
+                    rows[row].predictions.push((terminal, rule.id));
+                    continue;
+                };
+
+                match conflicts.iter_mut().find(|conflict| conflict.lhs == rule.lhs && conflict.terminal == terminal) {
+                    Some(conflict) => conflict.rules.push(rule.id),
+                    None => conflicts.push(LlConflict { lhs: rule.lhs.clone(), terminal, rules: vec![predicted, rule.id] })
+                }
+            }
+        }
+
+        let left_recursions = LlLeftRecursion::find(rules, &analysis);
+
+        if !conflicts.is_empty() || !left_recursions.is_empty() {
+            return Err(LlParserTableError { conflicts, left_recursions });
+        }
+
+        Ok(Self { rows })
+    }
+
+    /// Returns the rule to derive the non-terminal by, when the lookahead is the terminal.
+    ///
+    /// Only the predictions of the non-terminal are searched.
+    pub fn predict(&self, lhs: &SymDef::Class, terminal: &SymDef::Class) -> Option<usize> {
+        self.iter_predictions(lhs)
+            .find(|(lookahead, _)| *lookahead == terminal)
+            .map(|(_, rule_id)| rule_id)
+    }
+
+    /// Returns the lookaheads of the non-terminal, and the rule each of them predicts.
+    pub fn iter_predictions<'a>(&'a self, lhs: &'a SymDef::Class) -> impl Iterator<Item=(&'a SymDef::Class, usize)> + 'a {
+        self.rows
+            .iter()
+            .find(|row| row.lhs == *lhs)
+            .into_iter()
+            .flat_map(|row| row.predictions.iter().map(|(lookahead, rule_id)| (lookahead, *rule_id)))
+    }
+}
+
+/// Several rules of the non-terminal are predicted by the same lookahead.
+#[derive(Clone)]
+pub struct LlConflict<SymDef: SymbolDefinition> {
+    pub lhs: SymDef::Class,
+    pub terminal: SymDef::Class,
+    pub rules: Vec<usize>
+}
+
+impl<SymDef> Debug for LlConflict<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlConflict")
+            .field("lhs", &self.lhs)
+            .field("terminal", &self.terminal)
+            .field("rules", &self.rules)
+        .finish()
+    }
+}
+
+impl<SymDef> Display for LlConflict<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the rules {} of {:?} are all predicted by {:?}", self.rules.iter().join(", "), self.lhs, self.terminal)
+    }
+}
+
+/// The non-terminal derives a sequence starting with itself, a predictive parser would loop forever.
+#[derive(Clone)]
+pub struct LlLeftRecursion<SymDef: SymbolDefinition> {
+    /// The non-terminals, from the left-recursive one, each deriving a sequence starting with the next one.
+    pub path: Vec<SymDef::Class>,
+    /// Rules without the left recursion, rendered as "lhs → rhs", if it is direct.
+    pub suggestion: Vec<String>
+}
+
+impl<SymDef> Debug for LlLeftRecursion<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlLeftRecursion")
+            .field("path", &self.path)
+            .field("suggestion", &self.suggestion)
+        .finish()
+    }
+}
+
+impl<SymDef> Display for LlLeftRecursion<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self.path.iter().chain(self.path.first()).map(|class| format!("{:?}", class)).join(" → ");
+        write!(f, "{:?} is left-recursive ({})", self.path[0], path)?;
+
+        if self.suggestion.is_empty() {
+            return match self.path.get(1) {
+                Some(next) => write!(f, ", substitute the rules of {:?} into the ones of {:?} to make it direct", next, self.path[0]),
+                None => write!(f, ", through nullable symbols which must be expanded first")
+            };
+        }
+
+        write!(f, ", consider:")?;
+        self.suggestion.iter().try_for_each(|rule| write!(f, "\n    {}", rule))
+    }
+}
+
+impl<SymDef> LlLeftRecursion<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Find the left-recursive non-terminals.
+    ///
+    /// A non-terminal starts a rule if all the symbols before it are nullable.
+    fn find<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>, analysis: &GrammarAnalysis<SymDef>) -> Vec<Self> {
+        let nonterminals = rules.symbols().into_iter().filter(|sym| !sym.is_terminal()).collect::<Vec<_>>();
+
+        // The non-terminals starting a rule of each non-terminal.
+        let starts = |lhs: &SymDef::Class| -> Vec<SymDef::Class> {
+            let mut starts = vec![];
+
+            for rule in rules.iter_by_lhs(lhs.clone()) {
+                for sym in rule.rhs.iter() {
+                    if !sym.is_terminal() && !starts.contains(sym) {
+                        starts.push(sym.clone());
+                    }
+                    if !analysis.is_nullable(sym) {
+                        break;
+                    }
+                }
+            }
+
+            starts
+        };
+
+        let mut recursions = vec![];
+
+        for lhs in nonterminals.iter() {
+            // Breadth-first search of the shortest path back to the non-terminal.
+            let mut paths = vec![vec![lhs.clone()]];
+            let mut visited = vec![lhs.clone()];
+
+            let path = 'search: loop {
+                let mut next = vec![];
+
+                for path in paths {
+                    for sym in starts(path.last().unwrap()) {
+                        if sym == *lhs {
+                            break 'search Some(path);
+                        }
+
+                        if !visited.contains(&sym) {
+                            visited.push(sym.clone());
+                            next.push(path.iter().cloned().chain([sym]).collect::<Vec<_>>());
+                        }
+                    }
+                }
+
+                if next.is_empty() {
+                    break None;
+                }
+                paths = next;
+            };
+
+            if let Some(path) = path {
+                let suggestion = if path.len() == 1 { Self::suggest(rules, lhs) } else { vec![] };
+                recursions.push(Self { path, suggestion });
+            }
+        }
+
+        recursions
+    }
+
+    /// Rewrite the rules A → A α | β into A → β A', A' → α A' | ε.
+    fn suggest<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>, lhs: &SymDef::Class) -> Vec<String> {
+        let tail = format!("{:?}'", lhs);
+        let render = |syms: &[SymDef::Class], tail: &str| syms
+            .iter()
+            .map(|sym| format!("{:?}", sym))
+            .chain([tail.to_string()])
+            .join(" ");
+
+        let (recursive, others): (Vec<_>, Vec<_>) = rules
+            .iter_by_lhs(lhs.clone())
+            .partition(|rule| rule.rhs.first() == Some(lhs));
+
+        if recursive.is_empty() {
+            return vec![];
+        }
+
+        others
+            .iter()
+            .map(|rule| format!("{:?} → {}", lhs, render(&rule.rhs, &tail)))
+            .chain(recursive.iter().map(|rule| format!("{} → {}", tail, render(&rule.rhs[1..], &tail))))
+            .chain([format!("{} → ε", tail)])
+            .collect()
+    }
+}
+
+/// The grammar is not LL(1).
+#[derive(Clone)]
+pub struct LlParserTableError<SymDef: SymbolDefinition> {
+    pub conflicts: Vec<LlConflict<SymDef>>,
+    pub left_recursions: Vec<LlLeftRecursion<SymDef>>
+}
+
+impl<SymDef> Debug for LlParserTableError<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlParserTableError")
+            .field("conflicts", &self.conflicts)
+            .field("left_recursions", &self.left_recursions)
+        .finish()
+    }
+}
+
+impl<SymDef> Display for LlParserTableError<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "the grammar is not LL(1)")?;
+
+        for recursion in self.left_recursions.iter() {
+            writeln!(f, "- {}", recursion)?;
+        }
+
+        for conflict in self.conflicts.iter() {
+            writeln!(f, "- {}", conflict)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SymDef> std::error::Error for LlParserTableError<SymDef> where SymDef: SymbolDefinition {}
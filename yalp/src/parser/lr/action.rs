@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use crate::symbol::traits::SymbolDefinition;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LrParserOp {
     Shift(usize),
     Reduce(usize),
@@ -0,0 +1,170 @@
+use std::{borrow::Cow, fmt::{Debug, Display, Write}};
+
+use crate::{parser::{rule::ParserRuleSet, traits::ParserSymbolClass}, symbol::traits::SymbolDefinition};
+
+use super::{action::{LrParserAction, LrParserOp}, goto::LrParserGoto, state::LrParserState, table::LrParserTable};
+
+/// A parser table generated ahead of time, which can be loaded without recomputing the item sets.
+///
+/// The symbols are referred by their index in [ParserRuleSet::symbols].
+///
+/// # Exemple
+/// From a build script:
+/// ```ignore
+/// let table = LrParser::generate(&RULES)?.compile();
+/// std::fs::write(
+///     out_dir.join("table.rs"),
+///     format!("static TABLE: ::yalp::parser::lr::compiled::LrCompiledTable = {};", table.to_rust_source())
+/// )?;
+/// ```
+/// Then from the crate:
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/table.rs"));
+/// let parser = LrParser::load(&RULES, &TABLE)?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LrCompiledTable {
+    /// The fingerprint of the grammar the table was generated from.
+    pub fingerprint: u64,
+    pub states: Cow<'static, [LrCompiledState]>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LrCompiledState {
+    /// (terminal, op)
+    pub actions: Cow<'static, [(usize, LrParserOp)]>,
+    /// (non-terminal, next state)
+    pub gotos: Cow<'static, [(usize, usize)]>
+}
+
+/// The compiled table does not match the grammar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LrCompiledTableError {
+    /// The table was generated from another grammar.
+    FingerprintMismatch { expected: u64, got: u64 },
+    /// The table refers to a symbol the grammar does not have.
+    UnknownSymbol(usize),
+    /// The table refers to a state it does not have.
+    UnknownState(usize),
+    /// The table refers to a rule the grammar does not have.
+    UnknownRule(usize)
+}
+
+impl Display for LrCompiledTableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FingerprintMismatch { expected, got } => write!(
+                f,
+                "the table was generated from another grammar (fingerprint {:#x}, expecting {:#x}), it must be generated again",
+                got, expected
+            ),
+            Self::UnknownSymbol(sym) => write!(f, "the table refers to an unknown symbol #{}", sym),
+            Self::UnknownState(state) => write!(f, "the table refers to an unknown state #{}", state),
+            Self::UnknownRule(rule) => write!(f, "the table refers to an unknown rule #{}", rule),
+        }
+    }
+}
+
+impl std::error::Error for LrCompiledTableError {}
+
+impl LrCompiledTable {
+    /// Returns a Rust expression building the table, which can be assigned to a static.
+    pub fn to_rust_source(&self) -> String {
+        let mut src = String::new();
+
+        writeln!(src, "::yalp::parser::lr::compiled::LrCompiledTable {{").unwrap();
+        writeln!(src, "    fingerprint: {:#x},", self.fingerprint).unwrap();
+        writeln!(src, "    states: ::std::borrow::Cow::Borrowed(&[").unwrap();
+
+        for state in self.states.iter() {
+            let actions = state.actions
+                .iter()
+                .map(|(sym, op)| format!("({}, ::yalp::parser::lr::action::LrParserOp::{:?})", sym, op))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let gotos = state.gotos
+                .iter()
+                .map(|(sym, next)| format!("({}, {})", sym, next))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(src, "        ::yalp::parser::lr::compiled::LrCompiledState {{").unwrap();
+            writeln!(src, "            actions: ::std::borrow::Cow::Borrowed(&[{}]),", actions).unwrap();
+            writeln!(src, "            gotos: ::std::borrow::Cow::Borrowed(&[{}])", gotos).unwrap();
+            writeln!(src, "        }},").unwrap();
+        }
+
+        writeln!(src, "    ])").unwrap();
+        write!(src, "}}").unwrap();
+
+        src
+    }
+}
+
+impl<SymDef> LrParserTable<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Compile the table, the rules must be the ones it was generated from.
+    pub fn compile<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> LrCompiledTable {
+        let symbols = rules.symbols();
+        let index = |class: &SymDef::Class| symbols
+            .iter()
+            .position(|sym| sym == class)
+            .expect("the table refers to a symbol which is not in the rules");
+
+        LrCompiledTable {
+            fingerprint: rules.fingerprint(),
+            states: self
+                .iter()
+                .map(|state| LrCompiledState {
+                    actions: state.iter_actions().map(|a| (index(&a.r#type), a.op.clone())).collect(),
+                    gotos: state.iter_gotos().map(|g| (index(&g.r#type), g.next_state)).collect()
+                })
+                .collect()
+        }
+    }
+
+    /// Load a compiled table, checking it was generated from the rules.
+    pub fn load<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>, compiled: &LrCompiledTable) -> Result<Self, LrCompiledTableError> {
+        let expected = rules.fingerprint();
+        if compiled.fingerprint != expected {
+            return Err(LrCompiledTableError::FingerprintMismatch { expected, got: compiled.fingerprint });
+        }
+
+        let symbols = rules.symbols();
+        let symbol = |index: usize| symbols
+            .get(index)
+            .cloned()
+            .ok_or(LrCompiledTableError::UnknownSymbol(index));
+
+        let state = |index: usize| if index < compiled.states.len() {
+            Ok(index)
+        } else {
+            Err(LrCompiledTableError::UnknownState(index))
+        };
+
+        compiled.states
+            .iter()
+            .map(|compiled| {
+                let actions = compiled.actions
+                    .iter()
+                    .map(|(sym, op)| {
+                        match op {
+                            LrParserOp::Shift(next) => { state(*next)?; },
+                            LrParserOp::Reduce(rule) if rules.get(*rule).is_none() => return Err(LrCompiledTableError::UnknownRule(*rule)),
+                            _ => {}
+                        };
+
+                        Ok(LrParserAction { r#type: symbol(*sym)?, op: op.clone() })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let gotos = compiled.gotos
+                    .iter()
+                    .map(|(sym, next)| Ok(LrParserGoto { r#type: symbol(*sym)?, next_state: state(*next)? }))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(LrParserState::new_from_iterators(actions.into_iter(), gotos.into_iter()))
+            })
+            .collect()
+    }
+}
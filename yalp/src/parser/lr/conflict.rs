@@ -0,0 +1,110 @@
+use std::fmt::{Debug, Display};
+
+use crate::symbol::traits::SymbolDefinition;
+
+use super::action::LrParserOp;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LrConflictKind {
+    ShiftReduce,
+    ReduceReduce
+}
+
+impl Display for LrConflictKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ShiftReduce => f.write_str("shift/reduce"),
+            Self::ReduceReduce => f.write_str("reduce/reduce"),
+        }
+    }
+}
+
+/// One of the actions competing for the same terminal.
+#[derive(Clone, Debug)]
+pub struct LrConflictingAction {
+    pub op: LrParserOp,
+    /// The rules the action derives from, rendered as "lhs → rhs".
+    pub rules: Vec<String>
+}
+
+/// A state of the automaton with more than one action for a terminal.
+#[derive(Clone)]
+pub struct LrConflict<SymDef: SymbolDefinition> {
+    pub state: usize,
+    pub terminal: SymDef::Class,
+    /// The items of the state, with the position of the cursor.
+    pub items: Vec<String>,
+    pub actions: Vec<LrConflictingAction>
+}
+
+impl<SymDef> Debug for LrConflict<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LrConflict")
+            .field("state", &self.state)
+            .field("terminal", &self.terminal)
+            .field("items", &self.items)
+            .field("actions", &self.actions)
+        .finish()
+    }
+}
+
+impl<SymDef> LrConflict<SymDef> where SymDef: SymbolDefinition {
+    pub fn kind(&self) -> LrConflictKind {
+        if self.actions.iter().any(|a| matches!(a.op, LrParserOp::Shift(_))) {
+            LrConflictKind::ShiftReduce
+        } else {
+            LrConflictKind::ReduceReduce
+        }
+    }
+}
+
+impl<SymDef> Display for LrConflict<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} conflict in state {} on {:?}", self.kind(), self.state, self.terminal)?;
+
+        for item in self.items.iter() {
+            writeln!(f, "    {}", item)?;
+        }
+
+        for action in self.actions.iter() {
+            match action.op {
+                LrParserOp::Shift(state) => writeln!(f, "  shift to state {}, by:", state)?,
+                LrParserOp::Reduce(rule) => writeln!(f, "  reduce by rule {}:", rule)?,
+                LrParserOp::Accept => writeln!(f, "  accept by rule 0:")?,
+            }
+
+            for rule in action.rules.iter() {
+                writeln!(f, "    {}", rule)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The grammar cannot be turned into a deterministic LR table.
+#[derive(Clone)]
+pub struct LrParserTableError<SymDef: SymbolDefinition> {
+    pub conflicts: Vec<LrConflict<SymDef>>
+}
+
+impl<SymDef> Debug for LrParserTableError<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LrParserTableError").field("conflicts", &self.conflicts).finish()
+    }
+}
+
+impl<SymDef> Display for LrParserTableError<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} conflict(s) found in the grammar", self.conflicts.len())?;
+
+        for conflict in self.conflicts.iter() {
+            writeln!(f)?;
+            Display::fmt(conflict, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SymDef> std::error::Error for LrParserTableError<SymDef> where SymDef: SymbolDefinition {}
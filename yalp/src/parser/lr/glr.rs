@@ -0,0 +1,473 @@
+use std::{cell::RefCell, collections::HashMap, convert::Infallible, rc::Rc};
+
+use itertools::Itertools;
+
+use crate::{parser::{rule::{ParserRule, ParserRuleSet}, traits::{Parser, ParserSymbolClass}, ParserError}, span::Span, symbol::{traits::{Symbol, SymbolDefinition}, Sym}};
+
+use super::{action::LrParserOp, state::LrParserState, table::LrParserTable};
+
+/// Maximum number of parses listed by the ambiguity error.
+const GLR_MAX_PARSES: usize = 16;
+
+/// Generalized LR parser
+///
+/// The table is generated with its conflicts, the stack is forked on each of them,
+/// and the forks are dropped as soon as they cannot shift the next token.
+/// The forks reaching the same state after a token are merged, so the stacks form a graph,
+/// and the derivations of a symbol over the same tokens are packed in a single node.
+///
+/// The rules are only reduced once the input is accepted, and if it has a single parse.
+/// Otherwise the parsing fails, listing the alternative derivations.
+///
+/// There is no error recovery, the first syntax error aborts the parsing.
+pub struct GlrParser<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    pub table: LrParserTable<SymDef>
+}
+
+impl<'a, SymDef, Ctx> Parser<'a> for GlrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    type Symbol = Sym<SymDef>;
+    type SymbolDefinition = SymDef;
+    type GenerationError = Infallible;
+    type Context = Ctx;
+
+    fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition, Self::Context>) -> Result<Self, Self::GenerationError> {
+        Ok(Self {
+            table: LrParserTable::generate_with_conflicts(rules),
+            rules
+        })
+    }
+
+    fn parse_with_context<V, Token, TokenStream, TokenError>(&self, stream: TokenStream, ctx: &mut Self::Context) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>,
+            ParserError: From<TokenError>,
+            Self::Symbol: From<Token>,
+            <Self::Symbol as SymbolDefinition>::Value: TryInto<V>
+    {
+        let mut tokens: Vec<Option<Sym<SymDef>>> = vec![];
+        let mut heads = vec![Rc::new(GssNode::new(0))];
+
+        for tok in stream {
+            let tok: Sym<SymDef> = tok.map_err(|err| vec![err.into()])?.into();
+            let (shifted, accepted) = self.step(heads, &tok, tokens.len()).map_err(|err| vec![err])?;
+            tokens.push(Some(tok));
+
+            if let Some(root) = accepted {
+                self.select(&root, &tokens).map_err(|err| vec![err])?;
+                let sym = self.evaluate(&root, &mut tokens, ctx).map_err(|err| vec![err])?;
+                return sym.into_value().map_err(|err| vec![err]);
+            }
+
+            heads = shifted;
+        }
+
+        let span = tokens.last().and_then(|tok| tok.as_ref()).map(|tok| tok.span()).unwrap_or(Span::from(0));
+        Err(vec![ParserError::incomplete_input(span)])
+    }
+}
+
+impl<'a, SymDef, Ctx> GlrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Perform every action available for the token on each stack.
+    ///
+    /// Returns the heads of the stacks which shifted the token, and the root if the input is accepted.
+    fn step(&self, mut heads: Vec<Rc<GssNode>>, tok: &Sym<SymDef>, index: usize) -> Result<GlrStep, ParserError> {
+        let cursor = tok.span();
+        let root = self.rules
+            .root()
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), "the grammar has no rule"))?;
+
+        // A new link opens new paths to reduce, from the heads already reduced, so reduce until none is added.
+        loop {
+            let mut linked = false;
+
+            // The heads reached by the reductions are appended, and reduced within the same pass.
+            let mut i = 0;
+            while let Some(head) = heads.get(i).cloned() {
+                for action in self.state(head.state, &cursor)?.get_actions(tok.get_type()) {
+                    match action.op {
+                        LrParserOp::Reduce(rule_id) => linked |= self.reduce(&mut heads, &head, rule_id, &cursor)?,
+                        // The root rule may also be derived within itself, it is then reduced as any other rule.
+                        LrParserOp::Accept => {
+                            let paths = head.paths(root.rhs.len()).into_iter().filter(|(base, _)| !base.is_start()).collect();
+                            linked |= self.link(&mut heads, root, paths, &cursor)?;
+                        },
+                        LrParserOp::Shift(_) => {}
+                    }
+                }
+                i += 1;
+            }
+
+            if !linked {
+                break;
+            }
+        }
+
+        let token = Rc::new(GlrNode::Token { index, span: cursor.clone() });
+        let mut shifted: Vec<Rc<GssNode>> = vec![];
+        let mut accepted: Option<Rc<GlrNode>> = None;
+        let mut expected: Vec<SymDef::Class> = vec![];
+
+        for head in heads.iter() {
+            let state = self.state(head.state, &cursor)?;
+            let mut actions = state.get_actions(tok.get_type()).peekable();
+
+            if actions.peek().is_none() {
+                state.iter_terminals().for_each(|term| if !expected.contains(term) { expected.push(term.clone()) });
+                continue;
+            }
+
+            for action in actions {
+                match action.op {
+                    LrParserOp::Shift(next_state) => {
+                        let next = match shifted.iter().find(|node| node.state == next_state) {
+                            Some(next) => next.clone(),
+                            None => {
+                                let next = Rc::new(GssNode::new(next_state));
+                                shifted.push(next.clone());
+                                next
+                            }
+                        };
+                        next.links.borrow_mut().push((head.clone(), token.clone()));
+                    },
+                    LrParserOp::Accept => {
+                        // The input is accepted once the root rule is reduced from the start state.
+                        for (_, children) in head.paths(root.rhs.len()).into_iter().filter(|(base, _)| base.is_start()) {
+                            let derivation = GlrDerivation { rule_id: root.id, children };
+                            match &accepted {
+                                Some(root) => GlrNode::derive(root, derivation),
+                                None => accepted = Some(Rc::new(GlrNode::symbol(&cursor, derivation)))
+                            }
+                        }
+                    },
+                    LrParserOp::Reduce(_) => {}
+                }
+            }
+        }
+
+        if shifted.is_empty() && accepted.is_none() {
+            return Err(ParserError::unexpected_token(tok.clone(), expected));
+        }
+
+        Ok((shifted, accepted))
+    }
+
+    /// Returns the state of the table.
+    fn state(&self, state_id: usize, cursor: &Span) -> Result<&LrParserState<SymDef>, ParserError> {
+        self.table
+            .get(state_id)
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), format!("missing state {}", state_id)))
+    }
+
+    /// Reduce the rule along every path from the head, linking the node of the symbol to the head reached by its goto.
+    ///
+    /// The derivation is packed in the node of the symbol if the link already exists, returns true if a link was added.
+    fn reduce(&self, heads: &mut Vec<Rc<GssNode>>, head: &Rc<GssNode>, rule_id: usize, cursor: &Span) -> Result<bool, ParserError> {
+        let rule = self.rules
+            .get(rule_id)
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), format!("missing rule {}", rule_id)))?;
+
+        self.link(heads, rule, head.paths(rule.rhs.len()), cursor)
+    }
+
+    /// Reduce the rule along the paths, see [Self::reduce].
+    fn link(&self, heads: &mut Vec<Rc<GssNode>>, rule: &ParserRule<SymDef>, paths: Vec<GssPath>, cursor: &Span) -> Result<bool, ParserError> {
+        let rule_id = rule.id;
+        let mut linked = false;
+
+        for (base, children) in paths {
+            let next_state = self.state(base.state, cursor)?
+                .get_goto(&rule.lhs)
+                .ok_or_else(|| ParserError::missing_goto::<SymDef>(cursor.clone(), base.state, &rule.lhs))?
+                .next_state;
+
+            let derivation = GlrDerivation { rule_id, children };
+
+            let Some(next) = heads.iter().find(|node| node.state == next_state).cloned() else {
+                let node = GssNode::new(next_state);
+                node.links.borrow_mut().push((base, Rc::new(GlrNode::symbol(cursor, derivation))));
+                heads.push(Rc::new(node));
+                linked = true;
+                continue;
+            };
+
+            let symbol = next.links
+                .borrow()
+                .iter()
+                .find(|(below, _)| Rc::ptr_eq(below, &base))
+                .map(|(_, symbol)| symbol.clone());
+
+            match symbol {
+                Some(symbol) => GlrNode::derive(&symbol, derivation),
+                None => {
+                    next.links.borrow_mut().push((base, Rc::new(GlrNode::symbol(cursor, derivation))));
+                    linked = true;
+                }
+            }
+        }
+
+        Ok(linked)
+    }
+
+    /// Check the tree has a single parse, otherwise returns the ambiguity error listing the first ones.
+    fn select(&self, root: &Rc<GlrNode>, tokens: &[Option<Sym<SymDef>>]) -> Result<(), ParserError> {
+        let parses = root.count(&mut HashMap::new());
+
+        match parses {
+            1 => Ok(()),
+            _ => Err(ParserError::ambiguous(root.span().clone(), parses, self.render(root, tokens)))
+        }
+    }
+
+    /// Reduce the rules of the tree, bottom-up.
+    fn evaluate(&self, node: &GlrNode, tokens: &mut [Option<Sym<SymDef>>], ctx: &mut Ctx) -> Result<Sym<SymDef>, ParserError> {
+        match node {
+            GlrNode::Token { index, span } => tokens[*index]
+                .take()
+                .ok_or_else(|| ParserError::corrupt_table(span.clone(), format!("the token {} is reduced twice", index))),
+            GlrNode::Symbol { span, derivations } => {
+                let derivations = derivations.borrow();
+                let derivation = derivations
+                    .first()
+                    .ok_or_else(|| ParserError::corrupt_table(span.clone(), "a symbol has no derivation"))?;
+
+                let rule = self.rules
+                    .get(derivation.rule_id)
+                    .ok_or_else(|| ParserError::corrupt_table(span.clone(), format!("missing rule {}", derivation.rule_id)))?;
+
+                let syms = derivation.children
+                    .iter()
+                    .map(|child| self.evaluate(child, tokens, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Sym {
+                    span: span.clone(),
+                    class: rule.lhs.clone(),
+                    value: self.rules.execute(rule, syms, ctx)?
+                })
+            }
+        }
+    }
+
+    /// Render the first parses of the tree as "Lhs(child child ...)", tokens being rendered by their class.
+    fn render(&self, node: &GlrNode, tokens: &[Option<Sym<SymDef>>]) -> Vec<String> {
+        match node {
+            GlrNode::Token { index, .. } => vec![
+                tokens[*index]
+                    .as_ref()
+                    .map(|tok| format!("{:?}", tok.get_type()))
+                    .unwrap_or_default()
+            ],
+            GlrNode::Symbol { derivations, .. } => derivations
+                .borrow()
+                .iter()
+                .flat_map(|derivation| {
+                    let lhs = self.rules.get(derivation.rule_id).map(|rule| format!("{:?}", rule.lhs)).unwrap_or_default();
+
+                    derivation.children
+                        .iter()
+                        .map(|child| self.render(child, tokens))
+                        .multi_cartesian_product()
+                        .take(GLR_MAX_PARSES)
+                        .map(move |children| format!("{}({})", lhs, children.join(" ")))
+                        .collect::<Vec<_>>()
+                })
+                .take(GLR_MAX_PARSES)
+                .collect()
+        }
+    }
+}
+
+/// The heads of the stacks after a token, and the root of the parse forest if the input is accepted.
+type GlrStep = (Vec<Rc<GssNode>>, Option<Rc<GlrNode>>);
+
+/// The node reached by popping symbols, and the trees of the popped symbols
+type GssPath = (Rc<GssNode>, Vec<Rc<GlrNode>>);
+
+/// A node of the graph of the stacks, shared by the stacks reaching its state after the same token.
+struct GssNode {
+    state: usize,
+    /// The nodes below, and the tree of the symbol shifted from each of them
+    links: RefCell<Vec<(Rc<GssNode>, Rc<GlrNode>)>>
+}
+
+impl GssNode {
+    fn new(state: usize) -> Self {
+        Self { state, links: RefCell::new(vec![]) }
+    }
+
+    /// Returns true for the node of the start state, the only one without a node below.
+    fn is_start(&self) -> bool {
+        self.links.borrow().is_empty()
+    }
+
+    /// Returns the nodes reached by popping the count of symbols, with the trees of the popped symbols, in order.
+    fn paths(self: &Rc<Self>, count: usize) -> Vec<GssPath> {
+        if count == 0 {
+            return vec![(self.clone(), vec![])];
+        }
+
+        self.links
+            .borrow()
+            .iter()
+            .flat_map(|(below, tree)| below.paths(count - 1).into_iter().map(move |(base, mut trees)| {
+                trees.push(tree.clone());
+                (base, trees)
+            }))
+            .collect()
+    }
+}
+
+/// A node of the parse forest, shared by the stacks forked after it was pushed.
+enum GlrNode {
+    /// The token at the index of the input
+    Token { index: usize, span: Span },
+    /// A symbol, with every derivation of it over the same tokens
+    Symbol { span: Span, derivations: RefCell<Vec<GlrDerivation>> }
+}
+
+/// A derivation of a symbol, by the rule.
+struct GlrDerivation {
+    rule_id: usize,
+    children: Vec<Rc<GlrNode>>
+}
+
+impl GlrNode {
+    /// Returns the symbol derived by the rule, the span of an empty production being the empty one right before the lookahead.
+    fn symbol(cursor: &Span, derivation: GlrDerivation) -> Self {
+        let span = match (derivation.children.first(), derivation.children.last()) {
+            (Some(first), Some(last)) => first.span().join(last.span()),
+            _ => cursor.shrink_to_lo()
+        };
+
+        Self::Symbol { span, derivations: RefCell::new(vec![derivation]) }
+    }
+
+    /// Add the derivation to the symbol, unless it is already known.
+    fn derive(symbol: &GlrNode, derivation: GlrDerivation) {
+        if let Self::Symbol { derivations, .. } = symbol {
+            let known = derivations.borrow().iter().any(|other| {
+                other.rule_id == derivation.rule_id
+                && other.children.len() == derivation.children.len()
+                && other.children.iter().zip(derivation.children.iter()).all(|(a, b)| Rc::ptr_eq(a, b))
+            });
+
+            if !known {
+                derivations.borrow_mut().push(derivation);
+            }
+        }
+    }
+
+    fn span(&self) -> &Span {
+        match self {
+            Self::Token { span, .. } => span,
+            Self::Symbol { span, .. } => span
+        }
+    }
+
+    /// Returns the number of parses of the tree, the counts of the shared nodes being memoized by address.
+    fn count(&self, counts: &mut HashMap<*const GlrNode, usize>) -> usize {
+        let Self::Symbol { derivations, .. } = self else {
+            return 1;
+        };
+
+        if let Some(count) = counts.get(&(self as *const GlrNode)) {
+            return *count;
+        }
+
+        let count = derivations
+            .borrow()
+            .iter()
+            .map(|derivation| derivation.children.iter().fold(1_usize, |acc, child| acc.saturating_mul(child.count(counts))))
+            .fold(0_usize, |acc, count| acc.saturating_add(count));
+
+        counts.insert(self as *const GlrNode, count);
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::parser::{fixtures::{first, sum, tokens, Class, SymDef}, traits::Parser, rule::ParserRuleSet};
+
+    use super::GlrParser;
+
+    lazy_static! {
+        static ref AMBIGUOUS_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add(Class::E, [Class::E, Class::Plus, Class::E], &sum) // E → E + E
+        .add(Class::E, [Class::Num], &first) // E → num
+        .to_owned();
+
+        // Not LALR(1), reducing the letter requires two tokens of lookahead.
+        static ref LOOKAHEAD_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::Root, [Class::S], &first) // Root → S
+        .add(Class::S, [Class::A, Class::X, Class::Y], &|_| Ok(1)) // S → A x y
+        .add(Class::S, [Class::B, Class::X, Class::Z], &|_| Ok(2)) // S → B x z
+        .add(Class::A, [Class::Letter], &|_| Ok(0)) // A → letter
+        .add(Class::B, [Class::Letter], &|_| Ok(0)) // B → letter
+        .to_owned();
+
+        // The root non-terminal has several rules, and is derived within itself.
+        static ref NESTED_ROOT_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::List, [Class::List, Class::Plus, Class::Num], &sum) // List → List + num
+        .add(Class::List, [Class::LeftParen, Class::List, Class::RightParen], &|syms| Ok(syms[1].value)) // List → ( List )
+        .add(Class::List, [Class::Num], &first) // List → num
+        .to_owned();
+    }
+
+    #[test]
+    fn fork_on_conflicts() {
+        let parser = GlrParser::generate(&LOOKAHEAD_RULES).unwrap();
+
+        let value: i64 = parser.parse(tokens(&[(Class::Letter, 0), (Class::X, 0), (Class::Z, 0)])).unwrap();
+        assert_eq!(value, 2);
+
+        let value: i64 = parser.parse(tokens(&[(Class::Letter, 0), (Class::X, 0), (Class::Y, 0)])).unwrap();
+        assert_eq!(value, 1);
+
+        let errors = parser.parse::<i64, _, _, _>(tokens(&[(Class::Letter, 0), (Class::Y, 0)])).unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn report_ambiguities() {
+        let parser = GlrParser::generate(&AMBIGUOUS_RULES).unwrap();
+
+        let value: i64 = parser.parse(tokens(&[(Class::Num, 1), (Class::Plus, 0), (Class::Num, 2)])).unwrap();
+        assert_eq!(value, 3);
+
+        let errors = parser.parse::<i64, _, _, _>(tokens(&[
+            (Class::Num, 1), (Class::Plus, 0), (Class::Num, 2), (Class::Plus, 0), (Class::Num, 3)
+        ])).unwrap_err();
+
+        let message = errors[0].to_string();
+        assert!(message.starts_with("ambiguous input, 2 parses found:"), "{}", message);
+        assert!(message.contains("S(E(E(E(Num) Plus E(Num)) Plus E(Num)))"), "{}", message);
+        assert!(message.contains("S(E(E(Num) Plus E(E(Num) Plus E(Num))))"), "{}", message);
+    }
+
+    #[test]
+    fn merge_ambiguous_stacks() {
+        let parser = GlrParser::generate(&AMBIGUOUS_RULES).unwrap();
+
+        // 20 numbers have Catalan(19) parses, too many to explore one by one.
+        let input = (0..20).flat_map(|i| [(Class::Plus, 0), (Class::Num, i)]).skip(1).collect::<Vec<_>>();
+        let errors = parser.parse::<i64, _, _, _>(tokens(&input)).unwrap_err();
+
+        let message = errors[0].to_string();
+        assert!(message.starts_with("ambiguous input, 1767263190 parses found:"), "{}", message);
+        assert_eq!(message.lines().count(), 1 + 16 + 1);
+    }
+
+    #[test]
+    fn accept_the_root_rule_from_the_start_state() {
+        let parser = GlrParser::generate(&NESTED_ROOT_RULES).unwrap();
+        let input = [(Class::LeftParen, 0), (Class::Num, 1), (Class::Plus, 0), (Class::Num, 2), (Class::RightParen, 0), (Class::Plus, 0), (Class::Num, 3)];
+
+        assert_eq!(parser.parse::<i64, _, _, _>(tokens(&input)).unwrap(), 6);
+        assert!(parser.parse::<i64, _, _, _>(tokens(&input[..4])).is_err());
+        assert!(parser.parse::<i64, _, _, _>(tokens(&input[1..2])).is_err());
+    }
+}
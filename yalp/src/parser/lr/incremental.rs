@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+use crate::{parser::{traits::ParserSymbolClass, ParserError}, span::Span, symbol::{traits::{Symbol, SymbolDefinition}, Sym}};
+
+use super::{session::{LrParserSession, LrParserStatus, LrSessionState}, LrParser};
+
+/// The state of the parser before a token, sharing the stack with the states saved before it
+struct LrSnapshot<SymDef: SymbolDefinition> {
+    /// The index of the token
+    position: usize,
+    state: LrSessionState<SymDef>
+}
+
+/// An edit of the input, the text within the range is replaced by a text of the given length.
+pub struct LrEdit<SymDef: SymbolDefinition> {
+    /// The byte range of the replaced text, within the previous input
+    pub range: Range<usize>,
+    /// The length in bytes of the replacement text
+    pub len: usize,
+    /// The tokens of the text covered by [LrIncrementalParse::relex_range], once edited, with spans within the new input.
+    pub tokens: Vec<Sym<SymDef>>
+}
+
+/// The tokens of a parsed input, and the state of the parser before each of them.
+///
+/// On an edit, the parser resumes from the state saved before the first edited token,
+/// the rules reduced before it are not run again.
+pub struct LrIncrementalParse<SymDef: SymbolDefinition> {
+    tokens: Vec<Sym<SymDef>>,
+    snapshots: Vec<LrSnapshot<SymDef>>
+}
+
+impl<SymDef> LrIncrementalParse<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// The tokens of the input, ended by the end of stream token.
+    pub fn tokens(&self) -> &[Sym<SymDef>] {
+        &self.tokens
+    }
+
+    /// Returns the byte range of the previous input to lex again for an edit of the range.
+    ///
+    /// It is the range extended to the tokens it overlaps or touches, as the edit may merge or split them.
+    pub fn relex_range(&self, range: &Range<usize>) -> Range<usize> {
+        self.tokens[self.affected_tokens(range)]
+            .iter()
+            .filter_map(|tok| interval(&tok.span))
+            .fold(range.clone(), |acc, (from, to)| acc.start.min(from)..acc.end.max(to))
+    }
+
+    /// Returns the indices of the tokens overlapping or touching the range, the end of stream excluded.
+    fn affected_tokens(&self, range: &Range<usize>) -> Range<usize> {
+        let eos = <SymDef::Class as ParserSymbolClass>::eos();
+        let last = self.tokens.iter().position(|tok| *tok.get_type() == eos).unwrap_or(self.tokens.len());
+
+        let start = self.tokens[..last]
+            .iter()
+            .position(|tok| interval(&tok.span).is_some_and(|(_, to)| to >= range.start))
+            .unwrap_or(last);
+
+        let end = self.tokens[start..last]
+            .iter()
+            .position(|tok| interval(&tok.span).is_some_and(|(from, _)| from > range.end))
+            .map(|pos| start + pos)
+            .unwrap_or(last);
+
+        start..end
+    }
+}
+
+fn interval(span: &Span) -> Option<(usize, usize)> {
+    match span {
+        Span::Interval { from, to } => Some((*from, *to)),
+        _ => None
+    }
+}
+
+fn shift(span: &Span, delta: isize) -> Span {
+    match span {
+        Span::Interval { from, to } => Span::Interval {
+            from: from.saturating_add_signed(delta),
+            to: to.saturating_add_signed(delta)
+        },
+        _ => span.clone()
+    }
+}
+
+impl<'a, SymDef, Ctx> LrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Parse the tokens, saving the state of the parser before each of them, to parse them again after an edit.
+    pub fn parse_incremental<V>(&self, tokens: Vec<Sym<SymDef>>) -> (LrIncrementalParse<SymDef>, Result<V, Vec<ParserError>>)
+    where SymDef::Value: TryInto<V>, Ctx: Default
+    {
+        self.parse_incremental_with_context(tokens, &mut Default::default())
+    }
+
+    /// Parse the tokens incrementally, the reducers receive the context.
+    pub fn parse_incremental_with_context<V>(&self, tokens: Vec<Sym<SymDef>>, ctx: &mut Ctx) -> (LrIncrementalParse<SymDef>, Result<V, Vec<ParserError>>)
+    where SymDef::Value: TryInto<V>
+    {
+        let mut parse = LrIncrementalParse { tokens, snapshots: vec![] };
+        let result = self.resume(&mut parse, 0, ctx);
+        (parse, result)
+    }
+
+    /// Apply the edit, and parse again from the first token it affects.
+    ///
+    /// Returns the new value, and the span of the new input covered by the edited tokens.
+    pub fn reparse<V>(&self, parse: &mut LrIncrementalParse<SymDef>, edit: LrEdit<SymDef>) -> Result<(V, Span), Vec<ParserError>>
+    where SymDef::Value: TryInto<V>, Ctx: Default
+    {
+        self.reparse_with_context(parse, edit, &mut Default::default())
+    }
+
+    /// Apply the edit, and parse again from the first token it affects, the reducers receive the context.
+    ///
+    /// The rules reduced before the edit are not run again, so the context only sees the ones reduced from it on.
+    pub fn reparse_with_context<V>(&self, parse: &mut LrIncrementalParse<SymDef>, edit: LrEdit<SymDef>, ctx: &mut Ctx) -> Result<(V, Span), Vec<ParserError>>
+    where SymDef::Value: TryInto<V>
+    {
+        let affected = parse.affected_tokens(&edit.range);
+        let delta = edit.len as isize - edit.range.len() as isize;
+
+        let changed = edit.tokens
+            .iter()
+            .map(|tok| tok.span.clone())
+            .fold(Span::from(edit.range.start..edit.range.start + edit.len), |acc, span| acc.join(&span));
+
+        let suffix = parse.tokens
+            .split_off(affected.end)
+            .into_iter()
+            .map(|tok| Sym { span: shift(&tok.span, delta), ..tok });
+
+        parse.tokens.truncate(affected.start);
+        parse.tokens.extend(edit.tokens);
+        parse.tokens.extend(suffix);
+
+        self.resume(parse, affected.start, ctx).map(|value| (value, changed))
+    }
+
+    /// Restore the last state saved before the token at the position, and parse the tokens from it.
+    fn resume<V>(&self, parse: &mut LrIncrementalParse<SymDef>, position: usize, ctx: &mut Ctx) -> Result<V, Vec<ParserError>>
+    where SymDef::Value: TryInto<V>
+    {
+        let restored = parse.snapshots
+            .iter()
+            .rposition(|snapshot| snapshot.position <= position)
+            .and_then(|index| {
+                // The snapshot is saved again when the parser reaches its token.
+                parse.snapshots.truncate(index + 1);
+                parse.snapshots.pop()
+            });
+
+        let (start, mut session) = match restored {
+            Some(snapshot) => (snapshot.position, LrParserSession::restore(&self.table, self.rules, snapshot.state)),
+            None => {
+                parse.snapshots.clear();
+                (0, self.session())
+            }
+        };
+
+        for (position, tok) in parse.tokens.iter().enumerate().skip(start) {
+            parse.snapshots.push(LrSnapshot { position, state: session.save() });
+
+            match session.feed_with_context(tok.clone(), ctx) {
+                LrParserStatus::Accepted => break,
+                LrParserStatus::Error(errors) => return Err(errors),
+                LrParserStatus::NeedMore | LrParserStatus::Reduced(_) => {}
+            }
+        }
+
+        session.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::{parser::{fixtures::{first, Class, SymDef}, lr::LrParser, rule::ParserRuleSet, traits::Parser}, span::Span, symbol::Sym};
+
+    use super::LrEdit;
+
+    lazy_static! {
+        /// The context counts the additions reduced.
+        static ref RULES: ParserRuleSet<SymDef, usize> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add_with_context(Class::E, [Class::E, Class::Plus, Class::Num], &|syms, additions: &mut usize| { // E → E + num
+            *additions += 1;
+            Ok(syms[0].value + syms[2].value)
+        })
+        .add(Class::E, [Class::Num], &first) // E → num
+        .to_owned();
+    }
+
+    /// Split the text on whitespaces, the spans being shifted by the offset.
+    fn lex(text: &str, offset: usize) -> Vec<Sym<SymDef>> {
+        let mut tokens = vec![];
+        let mut from = 0;
+
+        for word in text.split(' ') {
+            let span = offset + from..offset + from + word.len();
+            match word {
+                "" => {},
+                "+" => tokens.push(Sym::new(span, Class::Plus, 0)),
+                num => tokens.push(Sym::new(span, Class::Num, num.parse::<i64>().unwrap()))
+            }
+            from += word.len() + 1;
+        }
+
+        tokens
+    }
+
+    fn lex_input(text: &str) -> Vec<Sym<SymDef>> {
+        let mut tokens = lex(text, 0);
+        tokens.push(Sym::new(text.len(), Class::EOS, 0));
+        tokens
+    }
+
+    /// Edit the text, and lex the tokens affected by the edit.
+    fn edit(text: &mut String, relex: std::ops::Range<usize>, range: std::ops::Range<usize>, replacement: &str) -> LrEdit<SymDef> {
+        text.replace_range(range.clone(), replacement);
+        let end = relex.end + replacement.len() - range.len();
+
+        LrEdit { range, len: replacement.len(), tokens: lex(&text[relex.start..end], relex.start) }
+    }
+
+    #[test]
+    fn reparse_from_the_edit() {
+        let parser = LrParser::generate(&RULES).unwrap();
+        let mut text = "1 + 2 + 3".to_string();
+
+        let mut additions = 0;
+        let (mut parse, value) = parser.parse_incremental_with_context::<i64>(lex_input(&text), &mut additions);
+        assert_eq!(value.unwrap(), 6);
+        assert_eq!(additions, 2);
+
+        // Only the last addition is reduced again.
+        let relex = parse.relex_range(&(8..9));
+        assert_eq!(relex, 8..9);
+
+        let mut additions = 0;
+        let (value, changed) = parser.reparse_with_context::<i64>(&mut parse, edit(&mut text, relex, 8..9, "30"), &mut additions).unwrap();
+        assert_eq!(value, 33);
+        assert!(matches!(changed, Span::Interval { from: 8, to: 10 }));
+        assert_eq!(additions, 1);
+
+        // Touching tokens are lexed again.
+        let relex = parse.relex_range(&(5..5));
+        assert_eq!(relex, 4..5);
+
+        let (value, _) = parser.reparse::<i64>(&mut parse, edit(&mut text, relex, 5..5, "0")).unwrap();
+        assert_eq!(value, 51);
+        assert_eq!(text, "1 + 20 + 30");
+        assert!(matches!(parse.tokens().last().unwrap().span, Span::Interval { from: 11, to: 11 }));
+    }
+
+    #[test]
+    fn recover_from_a_failed_parse() {
+        let parser = LrParser::generate(&RULES).unwrap();
+        let mut text = "1 + + 3".to_string();
+
+        let (mut parse, value) = parser.parse_incremental::<i64>(lex_input(&text));
+        assert!(value.is_err());
+
+        let relex = parse.relex_range(&(4..5));
+        let (value, changed) = parser.reparse::<i64>(&mut parse, edit(&mut text, relex, 4..5, "2 +")).unwrap();
+        assert_eq!(value, 6);
+        assert!(matches!(changed, Span::Interval { from: 4, to: 7 }));
+    }
+}
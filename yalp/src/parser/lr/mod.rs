@@ -1,116 +1,163 @@
 
-use crate::symbol::{traits::{SymbolDefinition, Symbol}, Sym};
+use std::sync::{Arc, OnceLock};
 
-use self::{table::LrParserTable, stack::LrParserStack, action::LrParserOp};
-use super::{rule::ParserRuleSet, traits::{Parser, ParserSymbolClass}, ParserError};
+use crate::{cst::{self, Cst, CstDefinition, CstToken}, span::Span, symbol::{traits::{SymbolDefinition, Symbol}, Sym}};
+
+use self::{table::LrParserTable, state::LrParserState, session::LrParserSession, conflict::LrParserTableError, compiled::{LrCompiledTable, LrCompiledTableError}};
+use super::{rule::{self, ParserRuleSet}, traits::{Parser, ParserSymbolClass}, ParserError};
 
 mod goto;
-mod action;
+pub mod action;
+pub mod compiled;
+pub mod conflict;
+pub mod glr;
+pub mod incremental;
+pub mod session;
 mod stack;
 mod state;
-mod table;
+pub mod table;
+
+/// Number of tokens to shift after an error, before reporting new syntax errors.
+const ERROR_RECOVERY_TOKENS: usize = 3;
+
+pub struct LrParser<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    pub(self) rules: &'a ParserRuleSet<SymDef, Ctx>,
+    pub table: table::LrParserTable<SymDef>,
+    /// Built on the first call to [LrParser::parse_cst]
+    cst: OnceLock<LrCstGrammar<SymDef>>
+}
+
+/// The rules and the table of the parser, over the symbols of the concrete syntax tree.
+struct LrCstGrammar<SymDef: SymbolDefinition> {
+    rules: ParserRuleSet<CstDefinition<SymDef>>,
+    table: LrParserTable<CstDefinition<SymDef>>
+}
+
+impl<'a, SymDef, Ctx> LrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Load the parser from a table compiled ahead of time, see [LrCompiledTable].
+    pub fn load(rules: &'a ParserRuleSet<SymDef, Ctx>, compiled: &LrCompiledTable) -> Result<Self, LrCompiledTableError> {
+        Ok(Self {
+            table: LrParserTable::load(rules, compiled)?,
+            rules,
+            cst: OnceLock::new()
+        })
+    }
+
+    /// Compile the table, to be loaded later on.
+    pub fn compile(&self) -> LrCompiledTable {
+        self.table.compile(self.rules)
+    }
+
+    /// Parse the tokens into a lossless concrete syntax tree, in place of running the reducers.
+    /// 
+    /// See [crate::lexer::Lexer::tokenize_lossless] to produce the tokens with their trivia.
+    pub fn parse_cst<TokenStream, TokenError>(&self, stream: TokenStream) -> Result<Cst<SymDef>, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<CstToken<SymDef>, TokenError>>,
+            ParserError: From<TokenError>
+    {
+        let LrCstGrammar { rules, table } = self.cst.get_or_init(|| LrCstGrammar {
+            rules: self.rules.with_reducer(rule::runner::ParserRuleRunner(Arc::new(|syms, _: &mut ()| cst::reduce::<SymDef>(syms))), &cst::error::<SymDef>),
+            table: self.table.cast()
+        });
+        let eos_class = <SymDef::Class as ParserSymbolClass>::eos();
+
+        // The end of stream token is never shifted, it is kept aside for its trivia.
+        let mut eos = None;
+        let stream = stream.inspect(|tok| match tok {
+            Ok(tok) if tok.class == eos_class => eos = Some(tok.clone()),
+            _ => {}
+        });
+
+        let root = LrParserSession::new(table, rules, 0).run(stream, &mut ())?;
 
-pub struct LrParser<'a, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
-    pub(self) rules: &'a ParserRuleSet<SymDef>,
-    pub table: table::LrParserTable<SymDef>
+        Ok(Cst {
+            root: root.into(),
+            eos: eos.unwrap_or_else(|| CstToken { class: eos_class, span: Span::from(0), text: String::new(), leading_trivia: vec![] })
+        })
+    }
 }
 
-impl<'a, SymDef> Parser<'a> for LrParser<'a, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+impl<'a, SymDef, Ctx> Parser<'a> for LrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
     type Symbol = Sym<SymDef>;
     type SymbolDefinition = SymDef;
+    type GenerationError = LrParserTableError<SymDef>;
+    type Context = Ctx;
 
-    fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition>) -> Self {
-        Self {
-            table: LrParserTable::generate(rules),
-            rules: rules
-        }
+    fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition, Self::Context>) -> Result<Self, Self::GenerationError> {
+        Ok(Self {
+            table: LrParserTable::generate(rules)?,
+            rules: rules,
+            cst: OnceLock::new()
+        })
     }
 
-    fn parse<V, Token, TokenStream, TokenError>(&self, stream: TokenStream) -> Result<V, ParserError>
+    fn parse_with_context<V, Token, TokenStream, TokenError>(&self, stream: TokenStream, ctx: &mut Self::Context) -> Result<V, Vec<ParserError>>
     where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
             ParserError: From<TokenError>,
             Self::Symbol: From<Token>,
             <Self::Symbol as SymbolDefinition>::Value: TryInto<V>
     {
-        let mut exec = LrParserExecution{
-            table: &self.table,
-            rules: &self.rules,
-            stack: LrParserStack::new(),
-            stream
-        };
-
-        let sym = exec.parse()?;
-        sym.into_value()
+        self.run(0, stream, ctx)
     }
 }
 
-struct LrParserExecution<'a, SymDef, Token, TokenStream, TokenError> 
-where   SymDef: SymbolDefinition, 
-        SymDef::Class: ParserSymbolClass,
-        Sym<SymDef>: From<Token>,
-        ParserError: From<TokenError>,
-        TokenStream: Iterator<Item = Result<Token, TokenError>>
-{
-    table: &'a LrParserTable<SymDef>,
-    rules: &'a ParserRuleSet<SymDef>,
-    stack: LrParserStack<SymDef>,
-    stream: TokenStream
+impl<'a, SymDef, Ctx> LrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Parse the tokens from the entry point, instead of the root, see [ParserRuleSet::entry].
+    pub fn parse_entry<V, Token, TokenStream, TokenError>(&self, entry: &str, stream: TokenStream) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
+            ParserError: From<TokenError>,
+            Sym<SymDef>: From<Token>,
+            SymDef::Value: TryInto<V>,
+            Ctx: Default
+    {
+        self.parse_entry_with_context(entry, stream, &mut Default::default())
+    }
+
+    /// Parse the tokens from the entry point, the reducers receive the context.
+    pub fn parse_entry_with_context<V, Token, TokenStream, TokenError>(&self, entry: &str, stream: TokenStream, ctx: &mut Ctx) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
+            ParserError: From<TokenError>,
+            Sym<SymDef>: From<Token>,
+            SymDef::Value: TryInto<V>
+    {
+        let state = self.start_state(entry).map_err(|err| vec![err])?;
+        self.run(state, stream, ctx)
+    }
+
+    /// Returns the start state of the entry point, the states of the root and of the entry points coming first in the table.
+    pub(self) fn start_state(&self, entry: &str) -> Result<usize, ParserError> {
+        let rule = self.rules
+            .get_entry(entry)
+            .ok_or_else(|| ParserError::new(Span::from(0), format!("unknown entry point {:?}", entry)))?;
+
+        self.rules
+            .starts()
+            .iter()
+            .position(|start| start.id == rule.id)
+            .ok_or_else(|| ParserError::corrupt_table(Span::from(0), format!("missing start state of the entry point {:?}", entry)))
+    }
+
+    /// Parse the tokens from the start state.
+    fn run<V, Token, TokenStream, TokenError>(&self, state: usize, stream: TokenStream, ctx: &mut Ctx) -> Result<V, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
+            ParserError: From<TokenError>,
+            Sym<SymDef>: From<Token>,
+            SymDef::Value: TryInto<V>
+    {
+        let sym = LrParserSession::new(&self.table, self.rules, state).run(stream, ctx)?;
+        sym.into_value().map_err(|err| vec![err])
+    }
 }
 
-impl<'a, SymDef, Token, TokenStream, TokenError> LrParserExecution<'a, SymDef, Token, TokenStream, TokenError> 
-where   SymDef: SymbolDefinition, 
-        SymDef::Class: ParserSymbolClass,
-        Sym<SymDef>: From<Token>,
-        ParserError: From<TokenError>,
-        TokenStream: Iterator<Item = Result<Token, TokenError>>
+/// The lookahead cannot be shifted from the state, the expected terminals are the ones it has an action for.
+fn unexpected_token<SymDef, Ctx>(rules: &ParserRuleSet<SymDef, Ctx>, state: &LrParserState<SymDef>, tok: &Sym<SymDef>) -> ParserError
+where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
 {
-    pub fn parse(&mut self) -> Result<Sym<SymDef>, ParserError> {
-        for tok in &mut self.stream {
-            let tok: Sym<SymDef> = tok.map_err(ParserError::from)?.into();
-            let cursor = tok.span();
-            self.stack.syms.push(tok.clone().into());
-            
-            let state_id = *self.stack.states.last().expect("parser stack is empty");
-            let state = self.table.get(state_id).unwrap();
-            let action = state.get_action(&tok.get_type())
-            .ok_or_else(||
-                ParserError::unexpected_token(
-                    tok, 
-                    state.iter_terminals().cloned().collect()
-                )
-            )?;
-
-            match action.op {
-                LrParserOp::Shift(next_state) => {
-                    // Shift to the next state
-                    self.stack.states.push(next_state);
-                },
-                LrParserOp::Reduce(rule_id) => {
-                    // Reduce the stack by the given rule
-                    let rule = self.rules.get(rule_id).expect(&format!("missing rule {rule_id}"));
-                    let nb_syms = rule.rhs.len();
-                    let syms = self.stack.pop(nb_syms).collect::<Vec<_>>();
-                    let sym_value = rule.execute(syms)?;
-                    
-                    let sym = Sym {
-                        span: cursor,
-                        class: rule.lhs.clone(),
-                        value: sym_value
-                    };
-
-                    self.stack.syms.push(sym.clone());
-
-                    // Fetch the next state to go to.
-                    let state = self.table.get(*self.stack.states.last().expect("expecting state to retrieve goto")).unwrap();
-                    let next_state = state.get_goto(&sym.get_type())
-                        .expect(&format!("unexpected symbol {:?}", sym.get_type()))
-                        .next_state;
-                    self.stack.states.push(next_state);
-                },
-                LrParserOp::Accept => break,
-            };
-        }
-
-        Ok(self.stack.syms.pop().unwrap())
-    }
-}
\ No newline at end of file
+    // The error symbol is never produced by the lexer.
+    let error_class = rules.get_error_symbol().map(|(class, _)| class);
+
+    ParserError::unexpected_token(
+        tok.clone(),
+        state.iter_terminals().filter(|class| Some(*class) != error_class).cloned().collect()
+    )
+}
@@ -0,0 +1,501 @@
+use std::rc::Rc;
+
+use crate::{parser::{rule::ParserRuleSet, traits::ParserSymbolClass, ParserError}, span::Span, symbol::{traits::{Symbol, SymbolDefinition}, Sym}};
+
+use super::{action::LrParserOp, stack::LrParserStack, table::LrParserTable, unexpected_token, LrParser, ERROR_RECOVERY_TOKENS};
+
+/// What happened to the token fed to a [LrParserSession].
+#[derive(Clone, Debug)]
+pub enum LrParserStatus {
+    /// The token was shifted, or discarded while recovering from a syntax error.
+    NeedMore,
+    /// The token was shifted, after reducing the stack by the rules, in order.
+    Reduced(Vec<usize>),
+    /// The root rule was reduced, the parsed value is returned by [LrParserSession::finish].
+    Accepted,
+    /// The parsing aborted on an unrecoverable error, returns every error found.
+    Error(Vec<ParserError>)
+}
+
+enum LrSessionPhase<SymDef: SymbolDefinition> {
+    Parsing,
+    /// The error symbol is shifted before the next token, as the erroneous token was discarded.
+    Recovering(ParserError),
+    /// The tokens are discarded until one is expected after the error symbol.
+    Discarding,
+    Accepted(Sym<SymDef>),
+    Aborted
+}
+
+impl<SymDef> Clone for LrSessionPhase<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Parsing => Self::Parsing,
+            Self::Recovering(error) => Self::Recovering(error.clone()),
+            Self::Discarding => Self::Discarding,
+            Self::Accepted(sym) => Self::Accepted(sym.clone()),
+            Self::Aborted => Self::Aborted
+        }
+    }
+}
+
+/// The state of a session between two tokens, see [super::incremental] which saves it before each token.
+pub(super) struct LrSessionState<SymDef: SymbolDefinition> {
+    stack: LrParserStack<SymDef>,
+    phase: LrSessionPhase<SymDef>,
+    /// The syntax errors recovered from so far, shared with the saved states
+    errors: Rc<Vec<ParserError>>,
+    /// Number of tokens left to shift before leaving the error recovery mode
+    recovering: usize
+}
+
+impl<SymDef> Clone for LrSessionState<SymDef> where SymDef: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self { stack: self.stack.clone(), phase: self.phase.clone(), errors: self.errors.clone(), recovering: self.recovering }
+    }
+}
+
+/// A push parser, the tokens are fed one at a time as they arrive, see [LrParser::session].
+///
+/// [LrParser::parse] feeds a session with the tokens of the stream, so both recover from syntax errors the same way.
+/// The session can be cloned between two tokens, to try several continuations of the input.
+///
+/// ```ignore
+/// let mut session = parser.session();
+///
+/// while let Some(tok) = frames.next().await {
+///     if let LrParserStatus::Error(errors) = session.feed(tok?) {
+///         return Err(errors);
+///     }
+/// }
+///
+/// let value: Expr = session.finish()?;
+/// ```
+pub struct LrParserSession<'a, SymDef, Ctx = ()> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    table: &'a LrParserTable<SymDef>,
+    rules: &'a ParserRuleSet<SymDef, Ctx>,
+    state: LrSessionState<SymDef>
+}
+
+impl<'a, SymDef, Ctx> Clone for LrParserSession<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    fn clone(&self) -> Self {
+        Self { table: self.table, rules: self.rules, state: self.state.clone() }
+    }
+}
+
+impl<'a, SymDef, Ctx> LrParser<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Start a session, to push the tokens to the parser instead of pulling them from a stream.
+    pub fn session(&self) -> LrParserSession<'_, SymDef, Ctx> {
+        LrParserSession::new(&self.table, self.rules, 0)
+    }
+
+    /// Start a session from the entry point, instead of the root, see [ParserRuleSet::entry].
+    pub fn entry_session(&self, entry: &str) -> Result<LrParserSession<'_, SymDef, Ctx>, ParserError> {
+        self.start_state(entry).map(|state| LrParserSession::new(&self.table, self.rules, state))
+    }
+}
+
+impl<'a, SymDef> LrParserSession<'a, SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Feed the next token, the input must be ended by the end of stream token.
+    pub fn feed<Token>(&mut self, tok: Token) -> LrParserStatus where Sym<SymDef>: From<Token> {
+        self.feed_with_context(tok, &mut ())
+    }
+}
+
+impl<'a, SymDef, Ctx> LrParserSession<'a, SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Start from the state of the table.
+    pub(super) fn new(table: &'a LrParserTable<SymDef>, rules: &'a ParserRuleSet<SymDef, Ctx>, state: usize) -> Self {
+        Self::restore(table, rules, LrSessionState {
+            stack: LrParserStack::starting_at(state),
+            phase: LrSessionPhase::Parsing,
+            errors: Rc::default(),
+            recovering: 0
+        })
+    }
+
+    /// Resume from a saved state.
+    pub(super) fn restore(table: &'a LrParserTable<SymDef>, rules: &'a ParserRuleSet<SymDef, Ctx>, state: LrSessionState<SymDef>) -> Self {
+        Self { table, rules, state }
+    }
+
+    /// Save the state of the session, to resume from it later on.
+    pub(super) fn save(&self) -> LrSessionState<SymDef> {
+        self.state.clone()
+    }
+
+    /// Feed the next token, the reducers run on it receive the context.
+    /// 
+    /// The same context should be given for every token of the input.
+    pub fn feed_with_context<Token>(&mut self, tok: Token, ctx: &mut Ctx) -> LrParserStatus where Sym<SymDef>: From<Token> {
+        let mut tok: Sym<SymDef> = tok.into();
+
+        match self.state.phase.clone() {
+            LrSessionPhase::Parsing => {},
+            LrSessionPhase::Recovering(error) => match self.shift_error(tok, error) {
+                Ok(Some(next)) => tok = next,
+                Ok(None) => return LrParserStatus::NeedMore,
+                Err(errors) => return LrParserStatus::Error(errors)
+            },
+            LrSessionPhase::Discarding => match self.discard(tok) {
+                Ok(Some(next)) => tok = next,
+                Ok(None) => return LrParserStatus::NeedMore,
+                Err(errors) => return LrParserStatus::Error(errors)
+            },
+            LrSessionPhase::Accepted(_) => return LrParserStatus::Error(self.fail(ParserError::unexpected_token(tok, vec![]))),
+            LrSessionPhase::Aborted => return LrParserStatus::Error(self.state.errors.to_vec())
+        }
+
+        let mut reduced = vec![];
+
+        // Reduce the stack until the lookahead can be shifted.
+        loop {
+            let cursor = tok.span();
+            let (_, state) = match self.state.stack.top_state(self.table, &cursor) {
+                Ok(top) => top,
+                Err(err) => return LrParserStatus::Error(self.fail(err))
+            };
+
+            let Some(action) = state.get_action(tok.get_type()) else {
+                let error = unexpected_token(self.rules, state, &tok);
+
+                match self.recover(tok, error) {
+                    Ok(Some(next)) => {
+                        tok = next;
+                        continue;
+                    },
+                    Ok(None) => return LrParserStatus::NeedMore,
+                    Err(errors) => return LrParserStatus::Error(errors)
+                }
+            };
+
+            match action.op {
+                LrParserOp::Shift(next_state) => {
+                    // Shift to the next state
+                    self.state.stack.push(next_state, tok);
+                    self.state.recovering = self.state.recovering.saturating_sub(1);
+
+                    return match reduced.is_empty() {
+                        true => LrParserStatus::NeedMore,
+                        false => LrParserStatus::Reduced(reduced)
+                    };
+                },
+                LrParserOp::Reduce(rule_id) => {
+                    // Reduce the stack by the given rule
+                    let sym = match self.state.stack.reduce(self.rules, rule_id, cursor.clone(), ctx) {
+                        Ok(sym) => sym,
+                        Err(err) => return LrParserStatus::Error(self.fail(err))
+                    };
+
+                    if let Err(err) = self.goto(sym, &cursor) {
+                        return LrParserStatus::Error(self.fail(err));
+                    }
+                    reduced.push(rule_id);
+                },
+                LrParserOp::Accept => {
+                    // Reduce the stack by the start rule
+                    let start = self.state.stack.start_rule(self.rules);
+
+                    let sym = match self.state.stack.reduce(self.rules, start, cursor.clone(), ctx) {
+                        Ok(sym) => sym,
+                        Err(err) => return LrParserStatus::Error(self.fail(err))
+                    };
+
+                    // The root rule may also be derived within itself, the input is only accepted once it is reduced from the start state.
+                    if self.state.stack.is_empty() {
+                        self.state.phase = LrSessionPhase::Accepted(sym);
+                        return LrParserStatus::Accepted;
+                    }
+
+                    if let Err(err) = self.goto(sym, &cursor) {
+                        return LrParserStatus::Error(self.fail(err));
+                    }
+                    reduced.push(start);
+                }
+            }
+        }
+    }
+
+    /// Push the reduced symbol, and the state it leads to from the top of the stack.
+    fn goto(&mut self, sym: Sym<SymDef>, cursor: &Span) -> Result<(), ParserError> {
+        let (state_id, state) = self.state.stack.top_state(self.table, cursor)?;
+
+        let Some(goto) = state.get_goto(sym.get_type()) else {
+            return Err(ParserError::missing_goto::<SymDef>(cursor.clone(), state_id, sym.get_type()));
+        };
+
+        self.state.stack.push(goto.next_state, sym);
+        Ok(())
+    }
+
+    /// Feed the tokens of the stream until the input is accepted, returns the parsed symbol, or every error found.
+    pub(super) fn run<Token, TokenStream, TokenError>(mut self, stream: TokenStream, ctx: &mut Ctx) -> Result<Sym<SymDef>, Vec<ParserError>>
+    where TokenStream: Iterator<Item = Result<Token, TokenError>>,
+            ParserError: From<TokenError>,
+            Sym<SymDef>: From<Token>
+    {
+        for tok in stream {
+            let tok = tok.map_err(|err| self.fail(err.into()))?;
+
+            match self.feed_with_context(tok, ctx) {
+                LrParserStatus::Accepted => break,
+                LrParserStatus::Error(errors) => return Err(errors),
+                LrParserStatus::NeedMore | LrParserStatus::Reduced(_) => {}
+            }
+        }
+
+        self.finish_symbol()
+    }
+
+    /// End the session, returns the parsed symbol, or every error found.
+    ///
+    /// The input is incomplete if the end of stream token was not accepted yet.
+    pub(super) fn finish_symbol(mut self) -> Result<Sym<SymDef>, Vec<ParserError>> {
+        match std::mem::replace(&mut self.state.phase, LrSessionPhase::Aborted) {
+            LrSessionPhase::Accepted(sym) if self.state.errors.is_empty() => Ok(sym),
+            LrSessionPhase::Accepted(_) | LrSessionPhase::Aborted => Err(Rc::unwrap_or_clone(self.state.errors)),
+            // The input ended while recovering from a syntax error.
+            LrSessionPhase::Recovering(_) | LrSessionPhase::Discarding => Err(self.fail(ParserError::unexpected_eof(self.state.stack.end_span()))),
+            LrSessionPhase::Parsing => Err(self.fail(ParserError::incomplete_input(self.state.stack.end_span())))
+        }
+    }
+
+    /// End the session, returns the parsed value, or every error found.
+    ///
+    /// The input is incomplete if the end of stream token was not accepted yet.
+    pub fn finish<V>(self) -> Result<V, Vec<ParserError>> where SymDef::Value: TryInto<V> {
+        self.finish_symbol()?.into_value().map_err(|err| vec![err])
+    }
+
+    /// The symbols on the stack, from the bottom.
+    pub fn symbols(&self) -> Vec<&Sym<SymDef>> {
+        self.state.stack.syms()
+    }
+
+    /// The states of the stack, from the initial one.
+    pub fn states(&self) -> Vec<usize> {
+        self.state.stack.states()
+    }
+
+    /// The syntax errors found so far.
+    pub fn errors(&self) -> &[ParserError] {
+        &self.state.errors
+    }
+
+    /// The terminals the next token may be, the error symbol excluded.
+    pub fn expected(&self) -> Vec<SymDef::Class> {
+        let error_class = self.rules.get_error_symbol().map(|(class, _)| class);
+
+        self.table
+            .get(self.state.stack.top_state_id())
+            .map(|state| state.iter_terminals().filter(|class| Some(*class) != error_class).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the root rule was reduced.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self.state.phase, LrSessionPhase::Accepted(_))
+    }
+
+    /// Returns true if the parsing aborted on an unrecoverable error.
+    pub fn is_aborted(&self) -> bool {
+        matches!(self.state.phase, LrSessionPhase::Aborted)
+    }
+
+    /// Recover from a syntax error, by shifting the error symbol, and discarding the tokens which cannot follow it.
+    ///
+    /// Returns the token from which the parsing resumes, none if it was discarded,
+    /// or every error found so far if the parser cannot recover.
+    fn recover(&mut self, tok: Sym<SymDef>, error: ParserError) -> Result<Option<Sym<SymDef>>, Vec<ParserError>> {
+        let eos = <SymDef::Class as ParserSymbolClass>::eos();
+
+        // Errors raised while recovering are most likely caused by the previous one.
+        if self.state.recovering == 0 {
+            Rc::make_mut(&mut self.state.errors).push(error.clone());
+        }
+
+        if self.rules.get_error_symbol().is_none() {
+            return Err(self.abort());
+        }
+
+        // Nothing was shifted since the last recovery, discard the token to move forward.
+        if self.state.recovering == ERROR_RECOVERY_TOKENS {
+            if *tok.get_type() == eos {
+                return Err(self.abort());
+            }
+
+            self.state.phase = LrSessionPhase::Recovering(error);
+            return Ok(None);
+        }
+
+        self.shift_error(tok, error)
+    }
+
+    /// Pop the stack until a state can shift the error symbol, shift it, then discard the tokens which cannot follow it.
+    fn shift_error(&mut self, tok: Sym<SymDef>, error: ParserError) -> Result<Option<Sym<SymDef>>, Vec<ParserError>> {
+        let Some((error_class, error_value)) = self.rules.get_error_symbol() else {
+            return Err(self.abort());
+        };
+
+        let next_state = loop {
+            let (_, state) = self.state.stack.top_state(self.table, &tok.span()).map_err(|err| self.fail(err))?;
+
+            if let Some(LrParserOp::Shift(next_state)) = state.get_action(error_class).map(|a| &a.op) {
+                break *next_state;
+            }
+
+            if self.state.stack.is_empty() {
+                return Err(self.abort());
+            }
+
+            self.state.stack.pop(1);
+        };
+
+        self.state.stack.push(next_state, Sym {
+            span: error.span(),
+            class: error_class.clone(),
+            value: error_value(&error)
+        });
+        self.state.recovering = ERROR_RECOVERY_TOKENS;
+
+        self.discard(tok)
+    }
+
+    /// Discard the token, unless it is expected after the error symbol.
+    fn discard(&mut self, tok: Sym<SymDef>) -> Result<Option<Sym<SymDef>>, Vec<ParserError>> {
+        let (_, state) = self.state.stack.top_state(self.table, &tok.span()).map_err(|err| self.fail(err))?;
+
+        if state.get_action(tok.get_type()).is_some() {
+            self.state.phase = LrSessionPhase::Parsing;
+            return Ok(Some(tok));
+        }
+
+        if *tok.get_type() == <SymDef::Class as ParserSymbolClass>::eos() {
+            return Err(self.abort());
+        }
+
+        self.state.phase = LrSessionPhase::Discarding;
+        Ok(None)
+    }
+
+    /// Abort the parsing on an unrecoverable error, returns every error found.
+    pub(super) fn fail(&mut self, error: ParserError) -> Vec<ParserError> {
+        Rc::make_mut(&mut self.state.errors).push(error);
+        self.abort()
+    }
+
+    fn abort(&mut self) -> Vec<ParserError> {
+        self.state.phase = LrSessionPhase::Aborted;
+        self.state.errors.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::parser::{fixtures::{expr_rules, first, sum, tok, tokens, Class, SymDef}, lr::{table::LrParserTable, LrParser}, rule::ParserRuleSet, traits::Parser, ParserErrorKind};
+
+    use super::LrParserStatus;
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef> = expr_rules();
+
+        static ref RECOVERING_RULES: ParserRuleSet<SymDef> = expr_rules()
+        .add(Class::E, [Class::Error], &|_| Ok(0)) // E → error
+        .error_symbol(Class::Error, &|_| 0)
+        .to_owned();
+
+        static ref ENTRY_RULES: ParserRuleSet<SymDef> = expr_rules()
+        .add(Class::T, [Class::Num], &|syms| first(syms).map(|value| value * 10)) // T → num
+        .entry("term", Class::T)
+        .to_owned();
+
+        static ref NESTED_ENTRY_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::E], &first) // S → E
+        .add(Class::E, [Class::E, Class::Plus, Class::Num], &sum) // E → E + num
+        .add(Class::E, [Class::Num], &|syms| first(syms).map(|value| value * 10)) // E → num
+        .entry("expr", Class::E)
+        .to_owned();
+    }
+
+    #[test]
+    fn feed_tokens_one_at_a_time() {
+        let parser = LrParser::generate(&RULES).unwrap();
+        let mut session = parser.session();
+
+        assert!(matches!(session.feed(tok(0, Class::Num, 1)), LrParserStatus::NeedMore));
+        assert!(matches!(session.feed(tok(1, Class::Plus, 0)), LrParserStatus::Reduced(rules) if rules == vec![2]));
+        assert_eq!(session.expected(), vec![Class::Num]);
+
+        // The session can go on from a copy.
+        let mut other = session.clone();
+        assert!(matches!(other.feed(tok(2, Class::Num, 5)), LrParserStatus::NeedMore));
+        assert!(matches!(other.feed(tok(3, Class::EOS, 0)), LrParserStatus::Accepted));
+        assert_eq!(other.finish::<i64>().unwrap(), 6);
+
+        assert!(matches!(session.feed(tok(2, Class::Num, 2)), LrParserStatus::NeedMore));
+        assert_eq!(session.symbols().len(), 3);
+        assert!(matches!(session.feed(tok(3, Class::EOS, 0)), LrParserStatus::Accepted));
+        assert!(session.is_accepted());
+        assert_eq!(session.finish::<i64>().unwrap(), 3);
+    }
+
+    #[test]
+    fn report_errors() {
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        let mut session = parser.session();
+        session.feed(tok(0, Class::Num, 1));
+        let errors = session.finish::<i64>().unwrap_err();
+        assert_eq!(errors[0].kind, ParserErrorKind::IncompleteInput);
+
+        let mut session = parser.session();
+        session.feed(tok(0, Class::Num, 1));
+        assert!(matches!(session.feed(tok(1, Class::Num, 2)), LrParserStatus::Error(errors) if errors.len() == 1));
+        assert!(session.is_aborted());
+    }
+
+    #[test]
+    fn recover_as_the_parser() {
+        let parser = LrParser::generate(&RECOVERING_RULES).unwrap();
+        let input = [(Class::Num, 1), (Class::Plus, 0), (Class::Plus, 0), (Class::Num, 2), (Class::Plus, 0), (Class::Num, 3)];
+
+        let mut session = parser.session();
+        for tok in tokens(&input) {
+            assert!(!matches!(session.feed(tok.unwrap()), LrParserStatus::Error(_)));
+        }
+        assert_eq!(session.errors().len(), 1);
+
+        let expected = parser.parse::<i64, _, _, _>(tokens(&input)).unwrap_err();
+        let errors = session.finish::<i64>().unwrap_err();
+        assert_eq!(errors.iter().map(|err| err.message.clone()).collect::<Vec<_>>(), expected.iter().map(|err| err.message.clone()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn start_from_an_entry_point() {
+        let parser = LrParser::generate(&ENTRY_RULES).unwrap();
+        assert_eq!(parser.parse::<i64, _, _, _>(tokens(&[(Class::Num, 2)])).unwrap(), 2);
+        assert_eq!(parser.parse_entry::<i64, _, _, _>("term", tokens(&[(Class::Num, 2)])).unwrap(), 20);
+        assert!(parser.parse_entry::<i64, _, _, _>("term", tokens(&[(Class::Num, 2), (Class::Plus, 0), (Class::Num, 1)])).is_err());
+
+        let errors = parser.parse_entry::<i64, _, _, _>("expr", tokens(&[])).unwrap_err();
+        assert_eq!(errors[0].message, "unknown entry point \"expr\"");
+
+        let mut session = parser.entry_session("term").unwrap();
+        assert!(matches!(session.feed(tok(0, Class::Num, 3)), LrParserStatus::NeedMore));
+        assert!(matches!(session.feed(tok(1, Class::EOS, 0)), LrParserStatus::Accepted));
+        assert_eq!(session.finish::<i64>().unwrap(), 30);
+    }
+
+    #[test]
+    fn start_from_a_nested_entry_point() {
+        let parser = LrParser::generate(&NESTED_ENTRY_RULES).unwrap();
+
+        // The rules of the non-terminal are still reduced from the root.
+        assert_eq!(parser.parse::<i64, _, _, _>(tokens(&[(Class::Num, 1)])).unwrap(), 10);
+        assert_eq!(parser.parse_entry::<i64, _, _, _>("expr", tokens(&[(Class::Num, 1)])).unwrap(), 10);
+        assert_eq!(parser.parse_entry::<i64, _, _, _>("expr", tokens(&[(Class::Num, 1), (Class::Plus, 0), (Class::Num, 2)])).unwrap(), 12);
+
+        assert!(LrParserTable::to_dot(&NESTED_ENTRY_RULES).contains("accept (__Start_expr → E) on EOS"));
+    }
+}
@@ -1,21 +1,167 @@
-use crate::symbol::{traits::SymbolDefinition, Sym};
+use std::rc::Rc;
 
+use crate::{parser::{rule::ParserRuleSet, traits::ParserSymbolClass, ParserError}, span::Span, symbol::{traits::SymbolDefinition, Sym}};
+
+use super::{state::LrParserState, table::LrParserTable};
+
+/// A state of the stack, and the symbol shifted to reach it.
+struct LrStackNode<SymDef: SymbolDefinition> {
+    state: usize,
+    /// None for the start state
+    sym: Option<Sym<SymDef>>,
+    parent: Option<Rc<LrStackNode<SymDef>>>
+}
+
+impl<SymDef: SymbolDefinition> Drop for LrStackNode<SymDef> {
+    /// Drop the unshared parents in a loop, as a recursive drop may overflow on a deep stack.
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+
+        while let Some(node) = parent {
+            parent = match Rc::try_unwrap(node) {
+                Ok(mut node) => node.parent.take(),
+                Err(_) => None
+            };
+        }
+    }
+}
+
+/// The stack of the parser, as a linked list whose nodes are shared by its copies.
+///
+/// A copy is made in constant time, the symbols being cloned only when a copy pops a shared node.
 pub(super) struct LrParserStack<SymDef: SymbolDefinition> {
-    pub(super) states: Vec<usize>,
-    pub(super) syms: Vec<Sym<SymDef>>,  
+    top: Rc<LrStackNode<SymDef>>,
+    /// Number of symbols on the stack
+    len: usize
+}
+
+impl<SymDef: SymbolDefinition> Clone for LrParserStack<SymDef> {
+    fn clone(&self) -> Self {
+        Self { top: self.top.clone(), len: self.len }
+    }
 }
 
 impl<SymDef: SymbolDefinition> LrParserStack<SymDef> {
-    pub fn new() -> Self {
+    /// Returns an empty stack, from the start state of an entry point.
+    pub fn starting_at(state: usize) -> Self {
         Self {
-            states: vec![0],
-            syms: vec![]
+            top: Rc::new(LrStackNode { state, sym: None, parent: None }),
+            len: 0
         }
     }
 
-    pub fn pop<'a>(&'a mut self, count: usize) -> impl Iterator<Item=Sym<SymDef>> + 'a {
-        let split = self.syms.len() - count;
-        self.states.drain(split..);
-        self.syms.drain(split..).into_iter()
+    /// Returns true if no symbol is on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Push the symbol, and the state reached after it.
+    pub fn push(&mut self, state: usize, sym: Sym<SymDef>) {
+        self.top = Rc::new(LrStackNode { state, sym: Some(sym), parent: Some(self.top.clone()) });
+        self.len += 1;
+    }
+
+    /// Pop the last symbols, and the states reached after them, returns the symbols in order.
+    pub fn pop(&mut self, count: usize) -> Vec<Sym<SymDef>> {
+        let mut syms = Vec::with_capacity(count);
+
+        for _ in 0..count.min(self.len) {
+            let parent = self.top.parent.clone().expect("a node with a symbol has a parent");
+
+            let top = std::mem::replace(&mut self.top, parent);
+            let sym = match Rc::try_unwrap(top) {
+                Ok(mut node) => node.sym.take(),
+                Err(node) => node.sym.clone()
+            };
+
+            syms.extend(sym);
+            self.len -= 1;
+        }
+
+        syms.reverse();
+        syms
+    }
+
+    /// Returns the nodes from the top of the stack.
+    fn iter(&self) -> impl Iterator<Item=&LrStackNode<SymDef>> {
+        std::iter::successors(Some(self.top.as_ref()), |node| node.parent.as_deref())
+    }
+
+    /// The symbols on the stack, from the bottom.
+    pub fn syms(&self) -> Vec<&Sym<SymDef>> {
+        let mut syms = self.iter().filter_map(|node| node.sym.as_ref()).collect::<Vec<_>>();
+        syms.reverse();
+        syms
+    }
+
+    /// The states of the stack, from the start state.
+    pub fn states(&self) -> Vec<usize> {
+        let mut states = self.iter().map(|node| node.state).collect::<Vec<_>>();
+        states.reverse();
+        states
+    }
+
+    /// Returns the id of the state on top of the stack.
+    pub fn top_state_id(&self) -> usize {
+        self.top.state
+    }
+
+    /// Returns the id of the start state, at the bottom of the stack.
+    pub fn start_state_id(&self) -> usize {
+        self.iter().last().map(|node| node.state).unwrap_or_default()
+    }
+}
+
+impl<SymDef> LrParserStack<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Returns the state on top of the stack, and its id.
+    pub fn top_state<'t>(&self, table: &'t LrParserTable<SymDef>, cursor: &Span) -> Result<(usize, &'t LrParserState<SymDef>), ParserError> {
+        let state_id = self.top_state_id();
+
+        table
+            .get(state_id)
+            .map(|state| (state_id, state))
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), format!("missing state {}", state_id)))
+    }
+
+    /// Returns the rule accepting the input, the start rule of the state at the bottom of the stack, see [ParserRuleSet::starts].
+    pub fn start_rule<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> usize {
+        rules.starts().get(self.start_state_id()).map(|rule| rule.id).unwrap_or(0)
+    }
+
+    /// Returns the empty span after the last symbol on the stack.
+    pub fn end_span(&self) -> Span {
+        self.top.sym
+            .as_ref()
+            .map(|sym| sym.span.shrink_to_hi())
+            .unwrap_or(Span::from(0))
+    }
+
+    /// Pop the symbols of the rule, and execute its reducer within the context.
+    /// 
+    /// The reduced symbol spans from its first to its last child, 
+    /// an empty production gets the empty span right before the lookahead.
+    pub fn reduce<Ctx>(&mut self, rules: &ParserRuleSet<SymDef, Ctx>, rule_id: usize, cursor: Span, ctx: &mut Ctx) -> Result<Sym<SymDef>, ParserError> {
+        let rule = rules
+            .get(rule_id)
+            .ok_or_else(|| ParserError::corrupt_table(cursor.clone(), format!("missing rule {}", rule_id)))?;
+
+        let nb_syms = rule.rhs.len();
+        if nb_syms > self.len {
+            return Err(ParserError::corrupt_table(cursor, format!("the rule {} pops more symbols than stacked", rule_id)));
+        }
+        let syms = self.pop(nb_syms);
+
+        let span = match (syms.first(), syms.last()) {
+            (Some(first), Some(last)) => first.span.join(&last.span),
+            _ => cursor.shrink_to_lo()
+        };
+
+        let sym_value = rules.execute(rule, syms, ctx)?;
+
+        Ok(Sym {
+            span,
+            class: rule.lhs.clone(),
+            value: sym_value
+        })
     }
 }
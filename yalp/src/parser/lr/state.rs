@@ -25,10 +25,26 @@ impl<SymDef: SymbolDefinition> LrParserState<SymDef> {
         }
     } 
 
+    /// Returns the same state, over another definition of the symbols sharing the same classes.
+    pub(super) fn cast<Other: SymbolDefinition<Class = SymDef::Class>>(&self) -> LrParserState<Other> {
+        LrParserState {
+            actions: self.actions.iter().map(|a| LrParserAction { r#type: a.r#type.clone(), op: a.op.clone() }).collect(),
+            goto: self.goto.iter().map(|g| LrParserGoto { r#type: g.r#type.clone(), next_state: g.next_state }).collect()
+        }
+    }
+
     pub(super) fn iter_terminals<'a>(&'a self) -> impl Iterator<Item=&'a SymDef::Class> + 'a {
         self.actions.iter().map(|a| &a.r#type)
     }
 
+    pub(super) fn iter_actions(&self) -> impl Iterator<Item=&LrParserAction<SymDef>> {
+        self.actions.iter()
+    }
+
+    pub(super) fn iter_gotos(&self) -> impl Iterator<Item=&LrParserGoto<SymDef>> {
+        self.goto.iter()
+    }
+
     pub(super) fn get_goto(&self, symbol: &SymDef::Class) -> Option<&LrParserGoto<SymDef>> {
         self.goto.iter().find(|a| a.r#type == *symbol)  
     }
@@ -36,4 +52,9 @@ impl<SymDef: SymbolDefinition> LrParserState<SymDef> {
     pub(super) fn get_action(&self, terminal: &SymDef::Class) -> Option<&LrParserAction<SymDef>> {
         self.actions.iter().find(|a| a.r#type == *terminal)
     }
+
+    /// Iterate over the actions for the terminal, a table generated with conflicts may have several of them.
+    pub(super) fn get_actions<'a>(&'a self, terminal: &'a SymDef::Class) -> impl Iterator<Item=&'a LrParserAction<SymDef>> + 'a {
+        self.actions.iter().filter(move |a| a.r#type == *terminal)
+    }
 }
\ No newline at end of file
@@ -2,8 +2,8 @@ use std::{collections::VecDeque, fmt::Debug};
 
 use itertools::Itertools;
 
-use crate::{parser::{rule::{ParserRuleSet, ParserRule}, traits::ParserSymbolClass}, symbol::{traits::SymbolDefinition, Sym}};
-use super::{state::LrParserState, action::{LrParserAction, LrParserOp}, goto::LrParserGoto};
+use crate::{parser::{rule::{ParserRuleSet, ParserRule, precedence::Resolution, analysis::GrammarAnalysis}, traits::ParserSymbolClass}, symbol::traits::SymbolDefinition};
+use super::{state::LrParserState, action::{LrParserAction, LrParserOp}, goto::LrParserGoto, conflict::{LrConflict, LrConflictingAction, LrParserTableError}};
 
 #[derive(Clone)]
 pub struct LrParserTable<SymDef: SymbolDefinition>(Vec<LrParserState<SymDef>>);
@@ -14,7 +14,7 @@ impl<SymDef> Debug for LrParserTable<SymDef> where SymDef: SymbolDefinition {
     }
 }
 
-impl<SymDef> FromIterator<LrParserState<SymDef>> for LrParserTable<SymDef> 
+impl<SymDef> FromIterator<LrParserState<SymDef>> for LrParserTable<SymDef>
 where SymDef: SymbolDefinition
 {
     fn from_iter<T: IntoIterator<Item = LrParserState<SymDef>>>(iter: T) -> Self {
@@ -22,8 +22,8 @@ where SymDef: SymbolDefinition
     }
 }
 
-impl<SymDef> LrParserTable<SymDef> 
-where SymDef: SymbolDefinition, 
+impl<SymDef> LrParserTable<SymDef>
+where SymDef: SymbolDefinition,
         SymDef::Class: ParserSymbolClass
 {
     /// Get the state by its id
@@ -31,34 +31,121 @@ where SymDef: SymbolDefinition,
         self.0.get(state)
     }
 
-    /// Generate the LrParserTable
-    pub fn generate(rules: &ParserRuleSet<SymDef>) -> Self {
+    /// Returns the same table, over another definition of the symbols sharing the same classes.
+    pub(super) fn cast<Other: SymbolDefinition<Class = SymDef::Class>>(&self) -> LrParserTable<Other> {
+        LrParserTable(self.0.iter().map(|state| state.cast()).collect())
+    }
+
+    /// Iterate over the states
+    pub fn iter(&self) -> impl Iterator<Item=&LrParserState<SymDef>> {
+        self.0.iter()
+    }
+
+    /// Generate the LALR(1) LrParserTable
+    ///
+    /// Fails if any state has more than one action for a terminal, listing every conflict.
+    pub fn generate<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> Result<Self, LrParserTableError<SymDef>> {
         let item_sets_table = ItemSetTable::<'_, >::build(rules);
 
+        let conflicts = item_sets_table.conflicts(rules);
+        if !conflicts.is_empty() {
+            return Err(LrParserTableError { conflicts });
+        }
+
+        Ok(Self::from_item_sets(&item_sets_table, rules))
+    }
+
+    /// Generate the LALR(1) LrParserTable, keeping every conflicting action.
+    /// 
+    /// A state may then have several actions for a terminal, see [super::glr::GlrParser] to explore all of them.
+    pub fn generate_with_conflicts<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> Self {
+        Self::from_item_sets(&ItemSetTable::build(rules), rules)
+    }
+
+    fn from_item_sets<Ctx>(item_sets_table: &ItemSetTable<'_, SymDef>, rules: &ParserRuleSet<SymDef, Ctx>) -> Self {
         item_sets_table
         .iter()
         .map(|s| {
             LrParserState::new_from_iterators(
-                s.iter_actions(), 
+                s.iter_actions(rules),
                 s.iter_gotos()
             )
         }).collect()
     }
+
+    /// Export the automaton of the grammar as a GraphViz DOT graph
+    /// 
+    /// States are labelled with their items and reductions, shifts are drawn as plain edges, gotos as dashed ones.
+    /// The grammar may have conflicts, the states having some are drawn in red.
+    pub fn to_dot<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> String {
+        ItemSetTable::build(rules).to_dot(rules)
+    }
+
+    /// Render the ACTION and GOTO tables, one row per state, and one column per symbol.
+    /// 
+    /// sN shifts to the state N, rN reduces by the rule N, acc accepts the input.
+    pub fn to_text<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> String {
+        let (terminals, non_terminals): (Vec<_>, Vec<_>) = rules
+            .symbols()
+            .into_iter()
+            .partition(|sym| sym.is_terminal());
+
+        let header = std::iter::once("state".to_string())
+            .chain(terminals.iter().chain(non_terminals.iter()).map(|sym| format!("{:?}", sym)))
+            .collect::<Vec<_>>();
+
+        let rows = self.iter().enumerate().map(|(id, state)| {
+            let actions = terminals.iter().map(|term| {
+                state.get_action(term).map(|action| match action.op {
+                    LrParserOp::Shift(next) => format!("s{}", next),
+                    LrParserOp::Reduce(rule) => format!("r{}", rule),
+                    LrParserOp::Accept => "acc".to_string()
+                }).unwrap_or_default()
+            });
+
+            let gotos = non_terminals.iter().map(|sym| {
+                state.get_goto(sym).map(|goto| goto.next_state.to_string()).unwrap_or_default()
+            });
+
+            std::iter::once(id.to_string()).chain(actions).chain(gotos).collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+
+        let widths = (0..header.len())
+            .map(|col| std::iter::once(&header).chain(rows.iter()).map(|row| row[col].chars().count()).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+
+        let format_row = |row: &[String]| row
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(col, (cell, width))| if col == 0 { format!("{:>width$}", cell) } else { format!("{:<width$}", cell) })
+            .join(" | ")
+            .trim_end()
+            .to_string();
+
+        let separator = widths.iter().map(|width| "-".repeat(*width)).join("-+-");
+
+        std::iter::once(format_row(&header))
+            .chain(std::iter::once(separator))
+            .chain(rows.iter().map(|row| format_row(row)))
+            .join("\n")
+    }
 }
 
-impl<'a, G> Into<Item<'a, G>> for &'a ParserRule<G> 
-where G: SymbolDefinition + 'static  
+impl<'a, G> Into<Item<'a, G>> for &'a ParserRule<G>
+where G: SymbolDefinition + 'static
 {
     fn into(self) -> Item<'a, G> {
-        Item(self, 0)
+        Item(self, 0, vec![])
     }
 }
 
-struct Item<'a, G>(&'a ParserRule<G>, usize) where G: SymbolDefinition + 'static;
+/// An LR(1) item: a rule, the position of the cursor, and the terminals which may follow the rule.
+struct Item<'a, G>(&'a ParserRule<G>, usize, Vec<G::Class>) where G: SymbolDefinition + 'static;
 
 impl<'a, SymDef> Debug for Item<'a, SymDef> where SymDef: SymbolDefinition{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.lhs.fmt(f)?;
+        f.write_str(&self.0.lhs_name())?;
         f.write_str(" → ")?;
         for (i, el) in self.0.rhs.iter().enumerate() {
             if i == self.1  {
@@ -73,6 +160,10 @@ impl<'a, SymDef> Debug for Item<'a, SymDef> where SymDef: SymbolDefinition{
             f.write_str("* ")?;
         }
 
+        if !self.2.is_empty() {
+            f.debug_set().entries(self.2.iter()).finish()?;
+        }
+
         Ok(())
     }
 
@@ -81,18 +172,26 @@ impl<'a, SymDef> Debug for Item<'a, SymDef> where SymDef: SymbolDefinition{
 
 impl<'a, G> Clone for Item<'a, G> where G: SymbolDefinition + 'static {
     fn clone(&self) -> Self {
-        Self(self.0, self.1.clone())
+        Self(self.0, self.1, self.2.clone())
     }
 }
 impl<'a, G> PartialEq for Item<'a, G> where G: SymbolDefinition + 'static {
     fn eq(&self, other: &Self) -> bool {
         *self.0 == *other.0 && self.1 == other.1
+        && self.2.len() == other.2.len()
+        && self.2.iter().all(|la| other.2.contains(la))
     }
 }
-impl<'a, SymDef> Item<'a, SymDef> 
+impl<'a, SymDef> Item<'a, SymDef>
 where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
     pub fn new(rule: &'a ParserRule<SymDef>, cursor: usize) -> Self {
-        Self(rule, cursor)
+        Self(rule, cursor, vec![])
+    }
+
+    /// Set the lookaheads of the item
+    pub fn with_lookaheads<It: IntoIterator<Item=SymDef::Class>>(mut self, lookaheads: It) -> Self {
+        self.merge_lookaheads(lookaheads);
+        self
     }
 
     pub fn rule_id(&self) -> usize {
@@ -103,64 +202,77 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
         self.1 += 1;
     }
 
-    pub(self) fn next_symbol(&self) -> Option<SymDef::Class> {
-        self.0.rhs.get(self.1).cloned()
-    }   
+    /// Check if both items share the same rule and cursor, regardless of their lookaheads.
+    pub fn same_core(&self, other: &Self) -> bool {
+        *self.0 == *other.0 && self.1 == other.1
+    }
 
-    // Build the closure of the item.
-    pub(self) fn close(self, rules: &'a ParserRuleSet<SymDef>) -> ItemSet<'a, SymDef> {
-        let mut set: ItemSet<'_, SymDef> = ItemSet::new();
-        let mut stack: VecDeque<Item<'_, SymDef>> = vec![self].into();
-        
-        while let Some(item) = stack.pop_front() {
-            // Add to the item set, if it returns false, we have already pushed the item in the set.
-            if !set.push(item.clone()) {
-                continue;
-            }
+    /// Add the lookaheads to the item, returns true if at least one was new.
+    pub fn merge_lookaheads<It: IntoIterator<Item=SymDef::Class>>(&mut self, lookaheads: It) -> bool {
+        let mut changed = false;
 
-            // No more symbols
-            if item.next_symbol().is_none() {
-                continue;
+        for la in lookaheads {
+            if !self.2.contains(&la) {
+                self.2.push(la);
+                changed = true;
             }
+        }
 
-            let sym = item.next_symbol().expect("item should have a symbol");
+        changed
+    }
 
-            // We found our terminal
-            if sym.is_terminal() {
-                continue;
-            }
+    pub(self) fn next_symbol(&self) -> Option<SymDef::Class> {
+        self.0.rhs.get(self.1).cloned()
+    }
+
+    /// Returns the items derived from the symbol following the cursor.
+    pub(self) fn derive<Ctx>(&self, rules: &'a ParserRuleSet<SymDef, Ctx>, firsts: &GrammarAnalysis<SymDef>) -> Vec<Item<'a, SymDef>> {
+        let Some(sym) = self.next_symbol() else {
+            return vec![];
+        };
 
-            // add our item in the stack for the next loop.
-            rules
-            .iter_by_lhs(sym)
-            .map(|r| r.into())
-            .for_each(|item| stack.push_back(item));
+        // We found our terminal
+        if sym.is_terminal() {
+            return vec![];
         }
 
+        // The lookaheads of the derived items are the terminals which can follow the symbol.
+        let lookaheads = firsts.first_of(&self.0.rhs[self.1 + 1..], &self.2);
+
+        rules
+        .iter_by_lhs(sym)
+        .map(|r| Item::new(r, 0).with_lookaheads(lookaheads.iter().cloned()))
+        .collect()
+    }
+
+    // Build the closure of the item.
+    pub(self) fn close<Ctx>(self, rules: &'a ParserRuleSet<SymDef, Ctx>, firsts: &GrammarAnalysis<SymDef>) -> ItemSet<'a, SymDef> {
+        let mut set: ItemSet<'_, SymDef> = ItemSet::new_with_items([self]);
+        set.close(rules, firsts);
         set
     }
 }
 
 struct ItemSet<'a, G>(Vec<Item<'a, G>>) where G: SymbolDefinition;
 
-impl<'a, SymDef> Debug for ItemSet<'a, SymDef> 
-where SymDef: SymbolDefinition 
+impl<'a, SymDef> Debug for ItemSet<'a, SymDef>
+where SymDef: SymbolDefinition
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("ItemSet").field(&self.0).finish()
     }
 }
 
-impl<'a, SymDef> FromIterator<Item<'a, SymDef>> for ItemSet<'a, SymDef> 
-where SymDef: SymbolDefinition 
+impl<'a, SymDef> FromIterator<Item<'a, SymDef>> for ItemSet<'a, SymDef>
+where SymDef: SymbolDefinition
 {
     fn from_iter<T: IntoIterator<Item = Item<'a, SymDef>>>(iter: T) -> Self {
         Self(iter.into_iter().collect())
     }
 }
 
-impl<'a, G> Clone for ItemSet<'a, G> 
-where G: SymbolDefinition 
+impl<'a, G> Clone for ItemSet<'a, G>
+where G: SymbolDefinition
 {
     fn clone(&self) -> Self {
         Self(self.0.clone())
@@ -168,16 +280,12 @@ where G: SymbolDefinition
 }
 impl<'a, G> PartialEq for ItemSet<'a, G> where G: SymbolDefinition {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0.len() == other.0.len() && self.0.iter().all(|item| other.0.contains(item))
     }
 }
-impl<'a, SymDef> ItemSet<'a, SymDef> 
+impl<'a, SymDef> ItemSet<'a, SymDef>
 where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
 {
-    pub fn new() -> Self {
-        Self(vec![])
-    }
-
     pub fn new_with_items<Itm: Into<Item<'a, SymDef>>, It: IntoIterator<Item = Itm>>(items: It) -> Self {
         Self(
             items
@@ -187,51 +295,51 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
         )
     }
 
-    /// Check if the item set is reducing
-    /// Returns the rule id by which the reduction occurs.
-    pub fn is_reducing(&self) -> Option<usize> {
-        self.0
-        .iter()
-        .find(|i| i.next_symbol().is_none())
-        .map(|i| i.rule_id())
-    }
-
-
-    /// Check if the item set is reducing
-    /// Returns the rule id by which the reduction occurs.
-    pub fn is_accepting(&self) -> Option<usize> {
+    /// Iterate over the reductions of the set.
+    /// Returns the rule id by which the reduction occurs, and the lookaheads triggering it.
+    pub fn iter_reductions(&self) -> impl Iterator<Item=(usize, &[SymDef::Class])> {
         self.0
         .iter()
-        .find(|i| i.next_symbol().is_none() && i.rule_id() == 0)
-        .map(|i| i.rule_id())
+        .filter(|i| i.next_symbol().is_none())
+        .map(|i| (i.rule_id(), i.2.as_slice()))
     }
 
     /// Returns the next item sets reachable from this set, grouped by the next symbol.
-    pub fn next_reachable_sets(&self, rules: &'a ParserRuleSet<SymDef>) -> Vec<(SymDef::Class, ItemSet<'a, SymDef>)> {
+    pub fn next_reachable_sets<Ctx>(&self, rules: &'a ParserRuleSet<SymDef, Ctx>, firsts: &GrammarAnalysis<SymDef>) -> Vec<(SymDef::Class, ItemSet<'a, SymDef>)> {
         self
         .split_by_symbol()
         .into_iter()
         // Generate next item sets
         .map(|(sym, mut set)| {
-            (sym, set.shift().close(rules).to_owned())
+            set.shift().close(rules, firsts);
+            (sym, set)
         })
         // Remove empty sets
         .filter(|(_, set)| !set.is_empty())
         .collect()
     }
 
-    /// Push a new item in the set, returns true if inserted
+    /// Push a new item in the set, or merge its lookaheads with the item sharing the same core.
+    /// Returns true if the set has changed.
     pub fn push(&mut self, item: Item<'a, SymDef>) -> bool {
-        if self.0.contains(&item) {
-            return false;
+        if let Some(existing) = self.0.iter_mut().find(|i| i.same_core(&item)) {
+            return existing.merge_lookaheads(item.2);
         }
         self.0.push(item);
-        return true;
+        true
     }
 
-    /// Append new items in the set
-    pub fn append<I: IntoIterator<Item=Item<'a, SymDef>>>(&mut self, items: I) {
-        items.into_iter().for_each(|item| {self.push(item);});
+    /// Append new items in the set, returns true if the set has changed.
+    pub fn append<I: IntoIterator<Item=Item<'a, SymDef>>>(&mut self, items: I) -> bool {
+        let mut changed = false;
+        items.into_iter().for_each(|item| changed |= self.push(item));
+        changed
+    }
+
+    /// Check if both sets have the same items, regardless of their lookaheads.
+    pub fn same_core(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+        && self.0.iter().all(|item| other.0.iter().any(|o| o.same_core(item)))
     }
 
     fn is_empty(&self) -> bool {
@@ -249,26 +357,40 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
 
     // Create a subset based on the next symbol for each item as group key.
     fn split_by_symbol(&self) -> Vec<(SymDef::Class, ItemSet<'a, SymDef>)> {
-        self
-        .0
-        .clone()
-        .into_iter()
-        .filter(|i| i.next_symbol().is_some())
-        .group_by(|i| i.next_symbol().expect("item should have a symbol"))
-        .into_iter()
-        .map(|(sym, group)| {
-            (sym, ItemSet(group.collect()))
-        }).collect::<Vec<_>>()
+        let mut groups: Vec<(SymDef::Class, ItemSet<'a, SymDef>)> = vec![];
+
+        for item in self.0.iter() {
+            let Some(sym) = item.next_symbol() else {
+                continue;
+            };
+
+            match groups.iter_mut().find(|(s, _)| *s == sym) {
+                Some((_, set)) => set.0.push(item.clone()),
+                None => groups.push((sym, ItemSet(vec![item.clone()])))
+            }
+        }
+
+        groups
     }
 
-    /// Close the item set, returns true if new item was added
-    fn close(&mut self, rules: &'a ParserRuleSet<SymDef>) -> &mut Self {
-        self.0
-        .clone()
-        .into_iter()
-        .map(|item| item.close(rules))
-        .for_each(|subset| self.append(subset.0));
-        
+    /// Close the item set, until no more items or lookaheads can be derived.
+    fn close<Ctx>(&mut self, rules: &'a ParserRuleSet<SymDef, Ctx>, firsts: &GrammarAnalysis<SymDef>) -> &mut Self {
+        let mut stack: VecDeque<usize> = (0..self.0.len()).collect();
+
+        while let Some(i) = stack.pop_front() {
+            for derived in self.0[i].derive(rules, firsts) {
+                let pos = self.0.iter().position(|item| item.same_core(&derived));
+
+                // Add to the item set, if it returns false, nothing new was learnt from the item.
+                if self.push(derived) {
+                    let pos = pos.unwrap_or(self.0.len() - 1);
+                    if !stack.contains(&pos) {
+                        stack.push_back(pos);
+                    }
+                }
+            }
+        }
+
         self
     }
 }
@@ -285,33 +407,22 @@ impl<'a, SymDef> Debug for ItemSetState<'a, SymDef> where SymDef: SymbolDefiniti
     }
 }
 
-impl<'a, SymDef> ItemSetState<'a, SymDef> 
-    where SymDef: SymbolDefinition, 
-            SymDef::Class: ParserSymbolClass 
+impl<'a, SymDef> ItemSetState<'a, SymDef>
+    where SymDef: SymbolDefinition,
+            SymDef::Class: ParserSymbolClass
 {
-
-    fn iter_terminal_symbols<'b>(&'b self) -> impl Iterator<Item=SymDef::Class> + 'b {
-        self.next_states
-        .iter()
-        .map(|(sym_type, _)| sym_type)
-        .cloned()
-        .filter(|sym_type| sym_type.is_terminal())
-        .map(|sym_type| sym_type)
-    }
-
     fn iter_terminal_transitions<'b>(&'b self) -> impl Iterator<Item=(SymDef::Class, usize)> + 'b {
         self.next_states
         .iter()
         .cloned()
         .filter(|(sym_type, _)| sym_type.is_terminal())
-        .map(|(sym_type, next_state)| (sym_type, next_state))     
     }
 
     pub fn iter_gotos<'b>(&'b self) -> impl Iterator<Item=LrParserGoto<SymDef>> + 'b {
         self.next_states
         .iter()
         .cloned()
-        .filter(|(sym_type, _)| !sym_type.is_terminal())  
+        .filter(|(sym_type, _)| !sym_type.is_terminal())
         .map(|(sym_type, next_state)| {
             LrParserGoto {
                 r#type: sym_type,
@@ -321,41 +432,145 @@ impl<'a, SymDef> ItemSetState<'a, SymDef>
     }
 
     /// Iterate over action-based state transitions
-    pub fn iter_actions<'b>(&'b self) -> Box<dyn Iterator<Item=LrParserAction<SymDef>> + 'b> {
-        if let Some(_) = self.set.is_accepting() {
-            return Box::new([
-                LrParserAction {
-                    r#type: <SymDef::Class as ParserSymbolClass>::eos(),
-                    op: LrParserOp::Accept
+    ///
+    /// Shift/reduce conflicts are resolved by the precedence of the rule, and the one of the lookahead.
+    /// The remaining conflicting actions are kept, shifts coming first.
+    pub fn iter_actions<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> impl Iterator<Item=LrParserAction<SymDef>> {
+        let starts = rules.starts().iter().map(|start| start.id).collect();
+        let actions = self.iter_candidate_actions(starts).collect::<Vec<_>>();
+        let mut discarded = vec![false; actions.len()];
+
+        for (s, shift) in actions.iter().enumerate().filter(|(_, a)| matches!(a.op, LrParserOp::Shift(_))) {
+            let Some(term_prec) = rules.terminal_precedence(&shift.r#type) else {
+                continue;
+            };
+
+            for (r, reduce) in actions.iter().enumerate().filter(|(_, a)| a.r#type == shift.r#type) {
+                let LrParserOp::Reduce(rule_id) = reduce.op else {
+                    continue;
+                };
+
+                let Some(rule_prec) = rules.get(rule_id).and_then(|rule| rules.rule_precedence(rule)) else {
+                    continue;
+                };
+
+                match rule_prec.resolve(&term_prec) {
+                    Resolution::Shift => discarded[r] = true,
+                    Resolution::Reduce => discarded[s] = true,
+                    Resolution::Error => {
+                        discarded[s] = true;
+                        discarded[r] = true;
+                    }
                 }
-            ].into_iter());
+            }
         }
-        if let Some(rule_id) = self.set.is_reducing() {
-            let it = self
-            .iter_terminal_symbols()
+
+        actions
+        .into_iter()
+        .zip(discarded)
+        .filter(|(_, discarded)| !discarded)
+        .map(|(action, _)| action)
+        .collect::<Vec<_>>()
+        .into_iter()
+    }
+
+    /// Iterate over every possible action, before any conflict resolution.
+    ///
+    /// A reduction only occurs on the lookaheads of its item, reducing a start rule on the end of stream accepts the input.
+    fn iter_candidate_actions<'b>(&'b self, starts: Vec<usize>) -> impl Iterator<Item=LrParserAction<SymDef>> + 'b {
+        let shifts = self
+        .iter_terminal_transitions()
+        .map(|(sym_type, next_state)| {
+            LrParserAction {
+                r#type: sym_type,
+                op: LrParserOp::Shift(next_state)
+            }
+        });
+
+        let reductions = self
+        .set
+        .iter_reductions()
+        .flat_map(move |(rule_id, lookaheads)| {
+            let starts = starts.clone();
+
+            lookaheads
+            .iter()
+            .cloned()
             .map(move |term| {
-                LrParserAction {
-                    r#type: term,
-                    op: LrParserOp::Reduce(rule_id)
-                }
-            });
+                let op = if starts.contains(&rule_id) && term == <SymDef::Class as ParserSymbolClass>::eos() {
+                    LrParserOp::Accept
+                } else {
+                    LrParserOp::Reduce(rule_id)
+                };
 
-            Box::new(it)
-        } else {
-            let it = self
-            .iter_terminal_transitions()
-            .map(|(sym_type, next_state)| {
-                LrParserAction {
-                    r#type: sym_type,
-                    op: LrParserOp::Shift(next_state)
-                }
-            });
+                LrParserAction { r#type: term, op }
+            })
+        });
+
+        shifts.chain(reductions)
+    }
 
-            Box::new(it)
+    /// Returns the conflicting actions of the state, grouped by terminal.
+    pub fn conflicts<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> Vec<LrConflict<SymDef>> {
+        let mut by_terminal: Vec<(SymDef::Class, Vec<LrParserOp>)> = vec![];
+
+        for action in self.iter_actions(rules) {
+            match by_terminal.iter_mut().find(|(term, _)| *term == action.r#type) {
+                Some((_, ops)) => ops.push(action.op),
+                None => by_terminal.push((action.r#type, vec![action.op]))
+            }
         }
+
+        by_terminal
+        .into_iter()
+        .filter(|(_, ops)| ops.len() > 1)
+        .map(|(terminal, ops)| {
+            let actions = ops
+            .into_iter()
+            .map(|op| {
+                let rules = match op {
+                    LrParserOp::Shift(_) => self.set.0
+                        .iter()
+                        .filter(|i| i.next_symbol().as_ref() == Some(&terminal))
+                        .map(|i| format_rule(i.0))
+                        .collect(),
+                    LrParserOp::Reduce(rule_id) => self.set.0
+                        .iter()
+                        .filter(|i| i.next_symbol().is_none() && i.rule_id() == rule_id)
+                        .map(|i| format_rule(i.0))
+                        .collect(),
+                    LrParserOp::Accept => self.set.0
+                        .iter()
+                        .filter(|i| i.next_symbol().is_none() && rules.starts().iter().any(|start| start.id == i.rule_id()))
+                        .map(|i| format_rule(i.0))
+                        .collect()
+                };
+
+                LrConflictingAction { op, rules }
+            })
+            .collect();
+
+            LrConflict {
+                state: self.id,
+                terminal,
+                items: self.set.0.iter().map(|i| format!("{:?}", i)).collect(),
+                actions
+            }
+        })
+        .collect()
     }
 }
 
+/// Escape the text to be used within a quoted DOT string.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Render the rule as "lhs → rhs".
+fn format_rule<SymDef: SymbolDefinition>(rule: &ParserRule<SymDef>) -> String {
+    format!("{} → {}", rule.lhs_name(), rule.rhs.iter().map(|sym| format!("{:?}", sym)).join(" "))
+}
+
 struct ItemSetTable<'a, G>(Vec<ItemSetState<'a, G>>) where G: SymbolDefinition;
 
 impl<'a, SymDef> Debug for ItemSetTable<'a, SymDef> where SymDef: SymbolDefinition {
@@ -370,40 +585,55 @@ impl<'a, G> Default for ItemSetTable<'a, G> where G: SymbolDefinition {
     }
 }
 
-impl<'a, SymDef> ItemSetTable<'a, SymDef>     
-where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass 
+impl<'a, SymDef> ItemSetTable<'a, SymDef>
+where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
 {
-    /// Build the item set transition table
-    pub fn build(rules: &'a ParserRuleSet<SymDef>) -> Self {
+    /// Build the LALR(1) item set transition table
+    ///
+    /// States sharing the same LR(0) core are merged, and their lookaheads are propagated
+    /// until no new lookahead is learnt.
+    pub fn build<Ctx>(rules: &'a ParserRuleSet<SymDef, Ctx>) -> Self {
+        let firsts = GrammarAnalysis::build(rules);
         let mut table = Self::default();
-        let mut stack: VecDeque<usize> = vec![
-            table.new_state(ItemSet::new_with_items([rules.root().expect("missing root rule")]))
-        ].into();
-        
+
+        // A start state for the root, then for each entry point, their ids being their positions.
+        let mut stack: VecDeque<usize> = rules
+            .starts()
+            .into_iter()
+            .map(|start| {
+                let item: Item<'a, SymDef> = start.into();
+                let item = item.with_lookaheads([<SymDef::Class as ParserSymbolClass>::eos()]);
+                table.new_state(item.close(rules, &firsts))
+            })
+            .collect();
+
+        assert!(!stack.is_empty(), "missing root rule");
+
         while let Some(state_id) = stack.pop_front() {
-            // Close the item set.
-            table.get_mut(state_id).unwrap().set.close(rules);
-  
             // Get the next states
             let next_states = table
             .get(state_id)
             .expect(&format!("missing state {state_id}"))
             .set
-            .to_owned()
-            .next_reachable_sets(rules)
+            .next_reachable_sets(rules, &firsts)
             .into_iter()
             .map(|(sym, set)| {
-                if let Some(state) = table.find_by_set(&set) {
+                if let Some(state) = table.find_by_core_mut(&set) {
+                    // New lookaheads must be propagated to the successors of the state.
+                    if state.set.append(set.0) && !stack.contains(&state.id) {
+                        stack.push_back(state.id);
+                    }
+
                     return (sym, state.id);
                 }
 
                 let state_id = table.new_state(set);
                 stack.push_back(state_id);
 
-                return (sym, state_id);
+                (sym, state_id)
             })
             .collect::<Vec<_>>();
-            
+
             table
             .get_mut(state_id)
             .expect(&format!("missing state {state_id}"))
@@ -413,23 +643,60 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
         table
     }
 
+    /// Returns every conflict of the automaton.
+    pub fn conflicts<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> Vec<LrConflict<SymDef>> {
+        self.0.iter().flat_map(|state| state.conflicts(rules)).collect()
+    }
+
     /// Iter over states
     pub fn iter(&self) -> impl Iterator<Item=&ItemSetState<'a, SymDef>> {
         self.0.iter()
     }
 
-    /// Add the ItemSet as a new, or existing state.
-    /// If the set is already bound to a state, does not create a new state. 
-    /// Returns (inserted, state id)
-    pub fn new_state(&mut self, set: ItemSet<'a, SymDef>) -> usize {
-        if let Some(state) = self.find_by_set(&set) {
-            return state.id;
+    /// Export the automaton as a GraphViz DOT graph
+    pub fn to_dot<Ctx>(&self, rules: &ParserRuleSet<SymDef, Ctx>) -> String {
+        let starts = rules.starts().iter().map(|start| start.id).collect::<Vec<_>>();
+        let mut dot = String::from("digraph automaton {\n    rankdir=LR;\n    node [shape=box, fontname=\"monospace\"];\n");
+
+        for state in self.iter() {
+            let mut label = format!("State {}\\l", state.id);
+
+            for item in state.set.0.iter() {
+                label += &format!("{}\\l", escape_dot(&format!("{:?}", item)));
+            }
+
+            for (rule_id, lookaheads) in state.set.iter_reductions() {
+                let op = if starts.contains(&rule_id) { "accept".to_string() } else { format!("reduce r{}", rule_id) };
+                let rule = rules.get(rule_id).map(format_rule).unwrap_or_default();
+                let lookaheads = lookaheads.iter().map(|la| format!("{:?}", la)).join(", ");
+                label += &format!("{} ({}) on {}\\l", op, escape_dot(&rule), escape_dot(&lookaheads));
+            }
+
+            let conflicts = state.conflicts(rules);
+            for conflict in conflicts.iter() {
+                label += &format!("{} conflict on {}\\l", conflict.kind(), escape_dot(&format!("{:?}", conflict.terminal)));
+            }
+
+            let color = if conflicts.is_empty() { "" } else { ", color=red, fontcolor=red" };
+            dot += &format!("    {} [label=\"{}\"{}];\n", state.id, label, color);
+
+            for (sym, next) in state.next_states.iter() {
+                let style = if sym.is_terminal() { "" } else { ", style=dashed" };
+                dot += &format!("    {} -> {} [label=\"{}\"{}];\n", state.id, next, escape_dot(&format!("{:?}", sym)), style);
+            }
         }
-        
+
+        dot += "}\n";
+        dot
+    }
+
+    /// Add the ItemSet as a new state.
+    /// Returns the state id
+    pub fn new_state(&mut self, set: ItemSet<'a, SymDef>) -> usize {
         let id = self.0.len();
         let state = ItemSetState{id, set, next_states: vec![]};
         self.0.push(state);
-        return id;
+        id
     }
 
     pub fn get_mut(&mut self, id: usize) -> Option<&mut ItemSetState<'a, SymDef>> {
@@ -440,9 +707,9 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
         self.0.get(id)
     }
 
-    /// Find a state by its item set.
-    pub fn find_by_set(&self, set: &ItemSet<'a, SymDef>) -> Option<&ItemSetState<'a, SymDef>> {
-        self.0.iter().find(|state| state.set == *set)
+    /// Find a state by the core of its item set.
+    pub fn find_by_core_mut(&mut self, set: &ItemSet<'a, SymDef>) -> Option<&mut ItemSetState<'a, SymDef>> {
+        self.0.iter_mut().find(|state| state.set.same_core(set))
     }
 }
 
@@ -450,9 +717,9 @@ where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
 mod test {
     use lazy_static::lazy_static;
 
-    use crate::{parser::{traits::{ParserSymbolClass, Parser}, rule::ParserRuleSet, ParserError, lr::{table::ItemSetTable, LrParser}}, symbol::{traits::SymbolDefinition, Sym}};
+    use crate::{parser::{traits::{ParserSymbolClass, Parser}, rule::ParserRuleSet, ParserError, lr::{table::ItemSetTable, LrParser}}, span::Span, symbol::{traits::SymbolDefinition, Sym}};
 
-    use super::{Item, ItemSet, LrParserTable};
+    use super::{GrammarAnalysis, Item, ItemSet, LrParserTable, LrParserOp, super::conflict::LrConflictKind};
 
 
     #[derive(Clone, Debug, PartialEq)]
@@ -464,6 +731,10 @@ mod test {
         One,
         Mult,
         Plus,
+        Minus,
+        Equal,
+        UMinus,
+        Error,
         EOS
     }
 
@@ -474,6 +745,10 @@ mod test {
                 Class::One => true,
                 Class::Mult => true,
                 Class::Plus => true,
+                Class::Minus => true,
+                Class::Equal => true,
+                Class::UMinus => true,
+                Class::Error => true,
                 Class::EOS => true,
                 _ => false
             }
@@ -492,7 +767,9 @@ mod test {
         Zero(Zero),
         One(One),
         Mult(Mult),
-        Plus(Plus)
+        Plus(Plus),
+        Expr(String),
+        EOS
     }
 
     struct SymDef;
@@ -514,6 +791,17 @@ mod test {
         }
     }
 
+    impl TryFrom<Value> for E {
+        type Error = ();
+
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+            match value {
+                Value::E(e) => Ok(e),
+                _ => Err(())
+            }
+        }
+    }
+
     impl TryFrom<Sym<SymDef>> for E {
         type Error = ParserError;
 
@@ -597,7 +885,7 @@ mod test {
             Class::E, [Class::E, Class::Mult, Class::B],
             &|mut syms| {
                 let mut e: E = syms.remove(0).try_into()?;
-                let b: B = syms.remove(2).try_into()?;
+                let b: B = syms.remove(1).try_into()?;
                 e.operations.push((BinOp::Mult, b));
                 Ok(e.into())
             }
@@ -606,7 +894,7 @@ mod test {
             Class::E, [Class::E, Class::Plus, Class::B],
             &|mut syms| {
                 let mut e: E = syms.remove(0).try_into()?;
-                let b: B = syms.remove(2).try_into()?;
+                let b: B = syms.remove(1).try_into()?;
                 e.operations.push((BinOp::Plus, b));
                 Ok(e.into())
             }            
@@ -628,21 +916,160 @@ mod test {
             }
         )
         .to_owned();
+
+        static ref AMBIGUOUS_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add( // S -> E
+            Class::S, [Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> E + E
+            Class::E, [Class::E, Class::Plus, Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> 0
+            Class::E, [Class::Zero],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .to_owned();
+
+        static ref PREC_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .right([Class::Equal])
+        .left([Class::Plus, Class::Minus])
+        .left([Class::Mult])
+        .right([Class::UMinus])
+        .add( // S -> E
+            Class::S, [Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> E + E
+            Class::E, [Class::E, Class::Plus, Class::E],
+            &binary
+        )
+        .add( // E -> E - E
+            Class::E, [Class::E, Class::Minus, Class::E],
+            &binary
+        )
+        .add( // E -> E * E
+            Class::E, [Class::E, Class::Mult, Class::E],
+            &binary
+        )
+        .add( // E -> E = E
+            Class::E, [Class::E, Class::Equal, Class::E],
+            &binary
+        )
+        .add( // E -> - E %prec UMinus
+            Class::E, [Class::Minus, Class::E],
+            &|mut syms| Ok(Value::Expr(format!("(-{})", expr(syms.remove(1)))))
+        )
+        .prec(Class::UMinus)
+        .add( // E -> 0
+            Class::E, [Class::Zero],
+            &|_| Ok(Value::Expr("0".into()))
+        )
+        .add( // E -> 1
+            Class::E, [Class::One],
+            &|_| Ok(Value::Expr("1".into()))
+        )
+        .to_owned();
+
+        static ref NONASSOC_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .nonassoc([Class::Equal])
+        .add( // S -> E
+            Class::S, [Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> E = E
+            Class::E, [Class::E, Class::Equal, Class::E],
+            &binary
+        )
+        .add( // E -> 0
+            Class::E, [Class::Zero],
+            &|_| Ok(Value::Expr("0".into()))
+        )
+        .to_owned();
+
+        static ref ERROR_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .error_symbol(Class::Error, &|_| Value::Expr("error".into()))
+        .add( // S -> E
+            Class::S, [Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> E B +
+            Class::E, [Class::E, Class::B, Class::Plus],
+            &|mut syms| Ok(Value::Expr(format!("{},{}", expr(syms.remove(0)), expr(syms.remove(0)))))
+        )
+        .add( // E -> B +
+            Class::E, [Class::B, Class::Plus],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> E error +
+            Class::E, [Class::E, Class::Error, Class::Plus],
+            &|mut syms| Ok(Value::Expr(format!("{},{}", expr(syms.remove(0)), expr(syms.remove(0)))))
+        )
+        .add( // B -> 0
+            Class::B, [Class::Zero],
+            &|_| Ok(Value::Expr("0".into()))
+        )
+        .add( // B -> 1
+            Class::B, [Class::One],
+            &|_| Ok(Value::Expr("1".into()))
+        )
+        .to_owned();
+
+        static ref SPAN_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add( // S -> B E
+            Class::S, [Class::B, Class::E],
+            &|syms| Ok(Value::Expr(format!("{:?} {:?}", syms[0].span, syms[1].span)))
+        )
+        .add( // B -> ε
+            Class::B, Vec::<Class>::new(),
+            &|_| Ok(Value::Expr("".into()))
+        )
+        .add( // E -> E + 0
+            Class::E, [Class::E, Class::Plus, Class::Zero],
+            &|_| Ok(Value::Expr("".into()))
+        )
+        .add( // E -> 0
+            Class::E, [Class::Zero],
+            &|_| Ok(Value::Expr("".into()))
+        )
+        .to_owned();
+
+        static ref REDUCE_REDUCE_RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add( // S -> E
+            Class::S, [Class::E],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> B
+            Class::E, [Class::B],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // E -> 0
+            Class::E, [Class::Zero],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .add( // B -> 0
+            Class::B, [Class::Zero],
+            &|mut syms| Ok(syms.remove(0).value)
+        )
+        .to_owned();
     }
 
     #[test]
     fn item_closure() {
+        let firsts = GrammarAnalysis::build(&RULES);
         let r0 = RULES.root().unwrap();
         let item: Item<'_, SymDef> = r0.into();
 
-        let closed_set = item.close(&RULES);
+        let closed_set = item.with_lookaheads([Class::EOS]).close(&RULES, &firsts);
+        let lookaheads = [Class::EOS, Class::Mult, Class::Plus];
         let expected_set = ItemSet::from_iter([
-            Item::new(RULES.get(0).unwrap(), 0),
-            Item::new(RULES.get(1).unwrap(), 0),
-            Item::new(RULES.get(2).unwrap(), 0),
-            Item::new(RULES.get(3).unwrap(), 0),
-            Item::new(RULES.get(4).unwrap(), 0),
-            Item::new(RULES.get(5).unwrap(), 0)
+            Item::new(RULES.get(0).unwrap(), 0).with_lookaheads([Class::EOS]),
+            Item::new(RULES.get(1).unwrap(), 0).with_lookaheads(lookaheads.clone()),
+            Item::new(RULES.get(2).unwrap(), 0).with_lookaheads(lookaheads.clone()),
+            Item::new(RULES.get(3).unwrap(), 0).with_lookaheads(lookaheads.clone()),
+            Item::new(RULES.get(4).unwrap(), 0).with_lookaheads(lookaheads.clone()),
+            Item::new(RULES.get(5).unwrap(), 0).with_lookaheads(lookaheads.clone())
         ]);
 
         assert_eq!(closed_set, expected_set);
@@ -651,11 +1078,12 @@ mod test {
 
     #[test]
     fn item_set_next_reachable_sets() {
+        let firsts = GrammarAnalysis::build(&RULES);
         let r0 = RULES.root().unwrap();
         let item: Item<'_, SymDef> = r0.into();
 
-        let closed_set = item.close(&RULES);
-        let next_item_sets = closed_set.next_reachable_sets(&RULES);
+        let closed_set = item.with_lookaheads([Class::EOS]).close(&RULES, &firsts);
+        let next_item_sets = closed_set.next_reachable_sets(&RULES, &firsts);
         
         println!("{:?}", next_item_sets);
     }
@@ -668,13 +1096,198 @@ mod test {
     
     #[test]
     fn table_generation() {
-        let table = LrParserTable::generate(&RULES);
+        let table = LrParserTable::generate(&RULES).unwrap();
         println!("{:?}", table)
     }
 
+    #[test]
+    fn shift_reduce_conflict() {
+        let err = LrParserTable::generate(&AMBIGUOUS_RULES).unwrap_err();
+
+        assert_eq!(err.conflicts.len(), 1);
+
+        let conflict = &err.conflicts[0];
+        assert_eq!(conflict.kind(), LrConflictKind::ShiftReduce);
+        assert_eq!(conflict.terminal, Class::Plus);
+        assert!(conflict.items.iter().any(|item| item.starts_with("E → E Plus E * ")));
+        assert!(matches!(conflict.actions[0].op, LrParserOp::Shift(_)));
+        assert_eq!(conflict.actions[1].op, LrParserOp::Reduce(1));
+        assert_eq!(conflict.actions[1].rules, vec!["E → E Plus E".to_string()]);
+        println!("{}", err);
+    }
+
+    #[test]
+    fn reduce_reduce_conflict() {
+        let err = LrParserTable::generate(&REDUCE_REDUCE_RULES).unwrap_err();
+
+        assert_eq!(err.conflicts.len(), 1);
+
+        let conflict = &err.conflicts[0];
+        assert_eq!(conflict.kind(), LrConflictKind::ReduceReduce);
+        assert_eq!(conflict.terminal, Class::EOS);
+        assert_eq!(conflict.actions.iter().map(|a| a.op.clone()).collect::<Vec<_>>(), vec![LrParserOp::Reduce(2), LrParserOp::Reduce(3)]);
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let parser = LrParser::generate(&PREC_RULES).unwrap();
+
+        let parse = |classes: Vec<Class>| -> String {
+            match parser.parse::<Value, _, _, _>(stream(classes)).unwrap() {
+                Value::Expr(e) => e,
+                value => panic!("unexpected value {:?}", value)
+            }
+        };
+
+        // 1 + 0 * 1 - 1
+        assert_eq!(
+            parse(vec![Class::One, Class::Plus, Class::Zero, Class::Mult, Class::One, Class::Minus, Class::One, Class::EOS]),
+            "((1 Plus (0 Mult 1)) Minus 1)"
+        );
+
+        // 1 = 0 = 1
+        assert_eq!(
+            parse(vec![Class::One, Class::Equal, Class::Zero, Class::Equal, Class::One, Class::EOS]),
+            "(1 Equal (0 Equal 1))"
+        );
+
+        // - 1 * 0
+        assert_eq!(
+            parse(vec![Class::Minus, Class::One, Class::Mult, Class::Zero, Class::EOS]),
+            "((-1) Mult 0)"
+        );
+    }
+
+    #[test]
+    fn non_associative_terminal() {
+        let parser = LrParser::generate(&NONASSOC_RULES).unwrap();
+
+        // 0 = 0
+        assert!(parser.parse::<Value, _, _, _>(stream(vec![Class::Zero, Class::Equal, Class::Zero, Class::EOS])).is_ok());
+        // 0 = 0 = 0
+        assert!(parser.parse::<Value, _, _, _>(stream(vec![Class::Zero, Class::Equal, Class::Zero, Class::Equal, Class::Zero, Class::EOS])).is_err());
+    }
+
+    #[test]
+    fn error_recovery() {
+        let parser = LrParser::generate(&ERROR_RULES).unwrap();
+
+        // 0 + 1 1 + 0 + 1 0 + 1 +
+        let errors = parser.parse::<Value, _, _, _>(stream(vec![
+            Class::Zero, Class::Plus, Class::One, Class::One, Class::Plus, Class::Zero, Class::Plus, 
+            Class::One, Class::Zero, Class::Plus, Class::One, Class::Plus, Class::EOS
+        ])).unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0].span(), Span::Interval { from: 3, .. }));
+        assert!(matches!(errors[1].span(), Span::Interval { from: 8, .. }));
+
+        // 0 + 1 +
+        let value = parser.parse::<Value, _, _, _>(stream(vec![Class::Zero, Class::Plus, Class::One, Class::Plus, Class::EOS])).unwrap();
+        assert!(matches!(value, Value::Expr(e) if e == "0,1"));
+    }
+
+    #[test]
+    fn no_error_recovery() {
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        // 1 1 0
+        let errors = parser.parse::<E, _, _, _>(stream(vec![Class::One, Class::One, Class::Zero, Class::EOS])).unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
     #[test]
     fn parser_test() {
-       let parser = LrParser::generate(&RULES);
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        // 1 + 1 * 0
+        let e: E = parser.parse(stream(vec![Class::One, Class::Plus, Class::One, Class::Mult, Class::Zero, Class::EOS])).unwrap();
+
+        assert!(matches!(e.lhs, B(1)));
+        assert!(matches!(e.operations.as_slice(), [(BinOp::Plus, B(1)), (BinOp::Mult, B(0))]));
+    }
+
+    #[test]
+    fn parser_reduces_on_lookaheads_only() {
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        // 1 1
+        assert!(parser.parse::<E, _, _, _>(stream(vec![Class::One, Class::One, Class::EOS])).is_err());
+    }
+
+    #[test]
+    fn reduced_symbols_span_their_children() {
+        let parser = LrParser::generate(&SPAN_RULES).unwrap();
+
+        // 0 + 0
+        let tokens = [Class::Zero, Class::Plus, Class::Zero, Class::EOS]
+            .into_iter()
+            .enumerate()
+            .map(|(i, class)| Ok::<_, ParserError>(Sym::new(Span::Interval { from: i, to: i + 1 }, class.clone(), token_value(class))));
+
+        let spans = parser.parse::<Value, _, _, _>(tokens).map(|value| match value {
+            Value::Expr(spans) => spans,
+            value => panic!("expecting an expression, got {:?}", value)
+        });
+
+        assert_eq!(spans.unwrap(), "Interval { from: 0, to: 0 } Interval { from: 0, to: 3 }");
+    }
+
+    #[test]
+    fn text_table() {
+        let table = LrParserTable::generate(&RULES).unwrap();
+        let text = table.to_text(&RULES);
+        let mut lines = text.lines();
+
+        assert_eq!(lines.next(), Some("state | EOS | Mult | Plus | Zero | One | S | E | B"));
+        assert_eq!(lines.next(), Some("------+-----+------+------+------+-----+---+---+--"));
+        assert_eq!(lines.next(), Some("    0 |     |      |      | s3   | s4  |   | 1 | 2"));
+        assert_eq!(lines.count(), 8);
+    }
+
+    #[test]
+    fn dot_graph() {
+        let dot = LrParserTable::to_dot(&RULES);
+
+        assert!(dot.starts_with("digraph automaton {"));
+        assert!(dot.contains("0 -> 1 [label=\"E\", style=dashed];"));
+        assert!(dot.contains("0 -> 3 [label=\"Zero\"];"));
+        assert!(dot.contains("accept (S → E) on EOS"));
+        assert!(!dot.contains("color=red"));
+
+        let dot = LrParserTable::to_dot(&AMBIGUOUS_RULES);
+        assert!(dot.contains("shift/reduce conflict on Plus"));
+        assert!(dot.contains("color=red"));
+    }
+
+    fn stream(classes: Vec<Class>) -> impl Iterator<Item=Result<Sym<SymDef>, ParserError>> {
+        classes
+        .into_iter()
+        .enumerate()
+        .map(|(i, class)| Ok(Sym::new(i, class.clone(), token_value(class))))
+    }
+
+    fn expr(sym: Sym<SymDef>) -> String {
+        match sym.value {
+            Value::Expr(e) => e,
+            value => panic!("expecting an expression, got {:?}", value)
+        }
+    }
+
+    fn binary(mut syms: Vec<Sym<SymDef>>) -> Result<Value, ParserError> {
+        let rhs = expr(syms.remove(2));
+        let op = syms.remove(1).class;
+        let lhs = expr(syms.remove(0));
+        Ok(Value::Expr(format!("({lhs} {op:?} {rhs})")))
+    }
 
+    fn token_value(class: Class) -> Value {
+        match class {
+            Class::Zero => Value::Zero(Zero),
+            Class::One => Value::One(One),
+            Class::Mult => Value::Mult(Mult),
+            Class::Plus => Value::Plus(Plus),
+            _ => Value::EOS
+        }
     }
 }
\ No newline at end of file
@@ -1,17 +1,62 @@
+use crate::source::SourceMap;
 use crate::span::Span;
 use crate::lexer::LexerError;
 use crate::symbol::Sym;
 use crate::symbol::traits::{SymbolDefinition, Symbol};
 
+use self::traits::ParserSymbolClass;
+
 pub mod lr;
+pub mod ll;
+pub mod earley;
+pub mod fuzz;
 pub mod rule;
 
+#[cfg(test)]
+pub(crate) mod fixtures;
+
 #[derive(Clone, Debug)]
 pub struct ParserError {
+    pub kind: ParserErrorKind,
     pub span: Span,
     pub message: String
 }
 
+/// What went wrong, see [ParserError::message] for the details.
+///
+/// The classes of the tokens are given by their display names, see [traits::ParserSymbolClass::display_name],
+/// and by their debug representations, to match them against the classes of the grammar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParserErrorKind {
+    /// The token cannot follow the input parsed so far.
+    UnexpectedToken(Box<UnexpectedToken>),
+    /// The stream ended while the parser was expecting more tokens.
+    UnexpectedEof,
+    /// The symbol holds a value of another type than the one of its class.
+    WrongValue,
+    /// The lexer failed to produce the next token.
+    Lexer,
+    /// Any other error, raised by the parser or by a reducer
+    Custom,
+    /// The stream ended before the parser accepted the input.
+    IncompleteInput,
+    /// The table refers to a state or a rule which does not exist, or pops more symbols than stacked.
+    CorruptTable,
+    /// The table has no transition for the reduced symbol.
+    MissingGoto
+}
+
+/// The unexpected token, and the expected ones, boxed to keep [ParserError] small.
+///
+/// Classes sharing a display name are listed once in `expected`, and each in `expected_classes`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnexpectedToken {
+    pub found: String,
+    pub expected: Vec<String>,
+    pub found_class: String,
+    pub expected_classes: Vec<String>
+}
+
 impl ToString for ParserError {
     fn to_string(&self) -> String {
         self.message.to_owned()
@@ -19,26 +64,115 @@ impl ToString for ParserError {
 }
 
 impl ParserError {
-    pub fn unexpected_token<SymDef: SymbolDefinition>(got: Sym<SymDef>, expecting: Vec<SymDef::Class>) -> Self {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
         Self {
+            kind: ParserErrorKind::Custom,
+            span,
+            message: message.into()
+        }
+    }
+
+    /// The token cannot follow the input parsed so far, the message reads as "expected `>` or attribute, found `/>`".
+    pub fn unexpected_token<SymDef: SymbolDefinition>(got: Sym<SymDef>, expecting: Vec<SymDef::Class>) -> Self 
+    where SymDef::Class: ParserSymbolClass
+    {
+        let found = got.class.display_name();
+        let mut expected: Vec<String> = vec![];
+
+        for name in expecting.iter().map(ParserSymbolClass::display_name) {
+            if !expected.contains(&name) {
+                expected.push(name);
+            }
+        }
+
+        let message = match expected.split_last() {
+            None => format!("unexpected {}", found),
+            Some((last, [])) => format!("expected {}, found {}", last, found),
+            Some((last, others)) => format!("expected {} or {}, found {}", others.join(", "), last, found)
+        };
+
+        Self {
+            kind: ParserErrorKind::UnexpectedToken(Box::new(UnexpectedToken {
+                found,
+                expected,
+                found_class: format!("{:?}", got.class),
+                expected_classes: expecting.iter().map(|class| format!("{:?}", class)).collect()
+            })),
             span: got.span().clone(),
-            message: format!("unexpecting token {:?}, expecting {:?}", got, expecting)
+            message
         }
     }
 
     pub fn wrong_value<SymDef: SymbolDefinition>(got: Sym<SymDef>) -> Self {
         Self {
+            kind: ParserErrorKind::WrongValue,
             span: got.span().clone(),
             message: format!("unexpecting value {:?}", got.value)
         }
     }
 
+    /// The input has several parses, the first alternatives are rendered as derivation trees.
+    pub fn ambiguous(span: Span, parses: usize, alternatives: Vec<String>) -> Self {
+        let more = match parses > alternatives.len() {
+            true => "\n  ...",
+            false => ""
+        };
+
+        Self {
+            kind: ParserErrorKind::Custom,
+            span,
+            message: format!(
+                "ambiguous input, {} parses found:\n{}{}",
+                parses,
+                alternatives.iter().map(|alt| format!("  {}", alt)).collect::<Vec<_>>().join("\n"),
+                more
+            )
+        }
+    }
+
+    pub fn unexpected_eof(span: Span) -> Self {
+        Self {
+            kind: ParserErrorKind::UnexpectedEof,
+            span,
+            message: "unexpected end of input".to_string()
+        }
+    }
+
+    pub fn incomplete_input(span: Span) -> Self {
+        Self {
+            kind: ParserErrorKind::IncompleteInput,
+            span,
+            message: "the input ended before it was accepted".to_string()
+        }
+    }
+
+    pub fn corrupt_table(span: Span, message: impl std::fmt::Display) -> Self {
+        Self {
+            kind: ParserErrorKind::CorruptTable,
+            span,
+            message: format!("corrupt parser table, {}", message)
+        }
+    }
+
+    pub fn missing_goto<SymDef: SymbolDefinition>(span: Span, state: usize, class: &SymDef::Class) -> Self {
+        Self {
+            kind: ParserErrorKind::MissingGoto,
+            span,
+            message: format!("corrupt parser table, no goto from state {} on {:?}", state, class)
+        }
+    }
 
     pub fn span(&self) -> Span {
         self.span.clone()
     }
+
+    /// Render the error, with the source line it occurs at.
+    pub fn render(&self, sources: &SourceMap) -> String {
+        sources.snippet(&self.span, &self.message)
+    }
 } 
 
+#[cfg(feature = "proc-macro")]
 impl ParserError {
     pub fn into_syn_error(self) -> syn::Error {
         let span: proc_macro2::Span = self.span().into();
@@ -49,6 +183,7 @@ impl ParserError {
 impl From<LexerError> for ParserError {
     fn from(value: LexerError) -> Self {
         Self {
+            kind: ParserErrorKind::Lexer,
             span: value.span,
             message: value.message
         }
@@ -66,12 +201,31 @@ pub mod traits {
     pub trait Parser<'a> {
         type Symbol: SymbolDefinition;
         type SymbolDefinition: SymbolDefinition;
+        /// Raised when the rules cannot be turned into a parser
+        type GenerationError;
+        /// Passed to the reducers, see [ParserRuleSet::add_with_context]
+        type Context;
 
         /// Generate the parser
-        fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition>) -> Self;
+        fn generate(rules: &'a ParserRuleSet<Self::SymbolDefinition, Self::Context>) -> Result<Self, Self::GenerationError> where Self: Sized;
+
+        /// Parse the stream of tokens, the reducers receive a default context.
+        /// 
+        /// Returns every syntax error found, if the grammar allows to recover from them.
+        fn parse<V, Token, TokenStream, TokenError>(&self, stream: TokenStream) -> Result<V, Vec<ParserError>>
+        where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
+                ParserError: From<TokenError>,
+                Self::Symbol: From<Token>,
+                <Self::Symbol as SymbolDefinition>::Value: TryInto<V>,
+                Self::Context: Default
+        {
+            self.parse_with_context(stream, &mut Default::default())
+        }
 
-        /// Parse the stream of tokens
-        fn parse<V, Token, TokenStream, TokenError>(&self, stream: TokenStream) -> Result<V, ParserError>
+        /// Parse the stream of tokens, the reducers receive the context.
+        /// 
+        /// Returns every syntax error found, if the grammar allows to recover from them.
+        fn parse_with_context<V, Token, TokenStream, TokenError>(&self, stream: TokenStream, ctx: &mut Self::Context) -> Result<V, Vec<ParserError>>
         where TokenStream: Iterator<Item = Result<Token, TokenError>>, 
                 ParserError: From<TokenError>,
                 Self::Symbol: From<Token>,
@@ -82,5 +236,10 @@ pub mod traits {
         fn is_terminal(&self) -> bool;
         // End of stream
         fn eos() -> Self;
+
+        /// The name of the class in error messages, such as "`>`" for a right angle.
+        fn display_name(&self) -> String {
+            format!("{:?}", self)
+        }
     }
 }
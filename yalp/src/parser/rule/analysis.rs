@@ -0,0 +1,365 @@
+use std::fmt::{Debug, Display};
+
+use crate::{parser::traits::ParserSymbolClass, symbol::traits::SymbolDefinition};
+
+use super::ParserRuleSet;
+
+/// The sets computed for a non-terminal
+struct NonTerminalSets<SymDef: SymbolDefinition> {
+    class: SymDef::Class,
+    /// Whether it can derive the empty sequence
+    nullable: bool,
+    /// The terminals which can start its derivations
+    first: Vec<SymDef::Class>,
+    /// The terminals which can follow it
+    follow: Vec<SymDef::Class>
+}
+
+/// The FIRST, FOLLOW and nullable sets of a grammar
+pub struct GrammarAnalysis<SymDef: SymbolDefinition> {
+    sets: Vec<NonTerminalSets<SymDef>>
+}
+
+impl<SymDef> Debug for GrammarAnalysis<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for sets in self.sets.iter() {
+            map.entry(&sets.class, &(sets.nullable, &sets.first, &sets.follow));
+        }
+        map.finish()
+    }
+}
+
+/// Push the terminals missing from the set, returns true if any was.
+fn extend<C: PartialEq + Clone>(set: &mut Vec<C>, terminals: &[C]) -> bool {
+    let mut changed = false;
+
+    for term in terminals {
+        if !set.contains(term) {
+            set.push(term.clone());
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+impl<SymDef> GrammarAnalysis<SymDef> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Compute the sets of the grammar, by iterating until a fixed point is reached.
+    pub fn build<Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> Self {
+        let mut analysis = Self {
+            sets: rules
+                .symbols()
+                .into_iter()
+                .filter(|sym| !sym.is_terminal())
+                .map(|class| NonTerminalSets { class, nullable: false, first: vec![], follow: vec![] })
+                .collect()
+        };
+
+        // FIRST and nullable sets
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for rule in rules.iter() {
+                let (first, nullable) = analysis.first_of_sequence(&rule.rhs);
+                let Some(sets) = analysis.get_mut(&rule.lhs) else {
+                    continue;
+                };
+
+                if nullable && !sets.nullable {
+                    sets.nullable = true;
+                    changed = true;
+                }
+
+                changed |= extend(&mut sets.first, &first);
+            }
+        }
+
+        // FOLLOW sets, the end of stream follows the root, and the entry points.
+        for start in rules.starts() {
+            if let Some(sets) = analysis.get_mut(&start.lhs) {
+                if !sets.follow.contains(&SymDef::Class::eos()) {
+                    sets.follow.push(SymDef::Class::eos());
+                }
+            }
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for rule in rules.iter() {
+                for (i, sym) in rule.rhs.iter().enumerate() {
+                    if sym.is_terminal() {
+                        continue;
+                    }
+
+                    let follow = analysis.first_of(&rule.rhs[i + 1..], &analysis.follow(&rule.lhs));
+
+                    if let Some(sets) = analysis.get_mut(sym) {
+                        changed |= extend(&mut sets.follow, &follow);
+                    }
+                }
+            }
+        }
+
+        analysis
+    }
+
+    fn get(&self, class: &SymDef::Class) -> Option<&NonTerminalSets<SymDef>> {
+        self.sets.iter().find(|sets| sets.class == *class)
+    }
+
+    fn get_mut(&mut self, class: &SymDef::Class) -> Option<&mut NonTerminalSets<SymDef>> {
+        self.sets.iter_mut().find(|sets| sets.class == *class)
+    }
+
+    /// Returns true if the symbol can derive the empty sequence.
+    pub fn is_nullable(&self, sym: &SymDef::Class) -> bool {
+        self.get(sym).is_some_and(|sets| sets.nullable)
+    }
+
+    /// Returns the terminals which can start a derivation of the symbol, a terminal starts itself.
+    pub fn first(&self, sym: &SymDef::Class) -> Vec<SymDef::Class> {
+        if sym.is_terminal() {
+            return vec![sym.clone()];
+        }
+
+        self.get(sym).map(|sets| sets.first.clone()).unwrap_or_default()
+    }
+
+    /// Returns the terminals which can follow the non-terminal.
+    pub fn follow(&self, sym: &SymDef::Class) -> Vec<SymDef::Class> {
+        self.get(sym).map(|sets| sets.follow.clone()).unwrap_or_default()
+    }
+
+    /// Returns the terminals which can start the sequence, followed by the lookaheads if the whole sequence is nullable.
+    pub fn first_of(&self, sequence: &[SymDef::Class], lookaheads: &[SymDef::Class]) -> Vec<SymDef::Class> {
+        let (mut first, nullable) = self.first_of_sequence(sequence);
+
+        if nullable {
+            extend(&mut first, lookaheads);
+        }
+
+        first
+    }
+
+    /// Returns the FIRST set of the sequence, and whether the sequence is nullable.
+    pub fn first_of_sequence(&self, sequence: &[SymDef::Class]) -> (Vec<SymDef::Class>, bool) {
+        let mut first: Vec<SymDef::Class> = vec![];
+
+        for sym in sequence {
+            extend(&mut first, &self.first(sym));
+
+            if !self.is_nullable(sym) {
+                return (first, false);
+            }
+        }
+
+        (first, true)
+    }
+}
+
+/// A defect of the grammar
+#[derive(Clone)]
+pub enum GrammarLint<SymDef: SymbolDefinition> {
+    /// The non-terminal cannot be derived from the root.
+    Unreachable(SymDef::Class),
+    /// The rule cannot derive any sequence of terminals, the parser will never reduce it.
+    Unproductive(usize),
+    /// The left-hand side of the rule is a terminal.
+    TerminalLhs(usize)
+}
+
+impl<SymDef> Debug for GrammarLint<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable(class) => f.debug_tuple("Unreachable").field(class).finish(),
+            Self::Unproductive(rule) => f.debug_tuple("Unproductive").field(rule).finish(),
+            Self::TerminalLhs(rule) => f.debug_tuple("TerminalLhs").field(rule).finish(),
+        }
+    }
+}
+
+impl<SymDef> PartialEq for GrammarLint<SymDef> where SymDef: SymbolDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unreachable(a), Self::Unreachable(b)) => a == b,
+            (Self::Unproductive(a), Self::Unproductive(b)) => a == b,
+            (Self::TerminalLhs(a), Self::TerminalLhs(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl<SymDef> Display for GrammarLint<SymDef> where SymDef: SymbolDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable(class) => write!(f, "the non-terminal {:?} cannot be reached from the root", class),
+            Self::Unproductive(rule) => write!(f, "the rule {} cannot derive any sequence of terminals", rule),
+            Self::TerminalLhs(rule) => write!(f, "the left-hand side of the rule {} is a terminal", rule),
+        }
+    }
+}
+
+/// Lint the grammar, returns its unreachable non-terminals, and its unproductive and terminal-headed rules.
+pub(super) fn lint<SymDef, Ctx>(rules: &ParserRuleSet<SymDef, Ctx>) -> Vec<GrammarLint<SymDef>>
+where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass
+{
+    let mut lints = vec![];
+
+    // Rules reducing a terminal
+    lints.extend(
+        rules
+        .iter()
+        .filter(|rule| rule.lhs.is_terminal())
+        .map(|rule| GrammarLint::TerminalLhs(rule.id))
+    );
+
+    // Non-terminals derivable from the root, or an entry point
+    let mut reachable: Vec<SymDef::Class> = vec![];
+    for start in rules.starts() {
+        if !reachable.contains(&start.lhs) {
+            reachable.push(start.lhs.clone());
+        }
+    }
+    let mut i = 0;
+    while i < reachable.len() {
+        let rhs = rules
+            .iter_by_lhs(reachable[i].clone())
+            .flat_map(|rule| rule.rhs.iter())
+            .filter(|sym| !sym.is_terminal())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for sym in rhs {
+            if !reachable.contains(&sym) {
+                reachable.push(sym);
+            }
+        }
+        i += 1;
+    }
+
+    lints.extend(
+        rules
+        .symbols()
+        .into_iter()
+        .filter(|sym| !sym.is_terminal() && !reachable.contains(sym))
+        .map(GrammarLint::Unreachable)
+    );
+
+    // Non-terminals deriving a sequence of terminals
+    let mut productive: Vec<SymDef::Class> = vec![];
+    let is_productive = |productive: &Vec<SymDef::Class>, rule: &super::ParserRule<SymDef>| {
+        rule.rhs.iter().all(|sym| sym.is_terminal() || productive.contains(sym))
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for rule in rules.iter() {
+            if !productive.contains(&rule.lhs) && is_productive(&productive, rule) {
+                productive.push(rule.lhs.clone());
+                changed = true;
+            }
+        }
+    }
+
+    lints.extend(
+        rules
+        .iter()
+        .filter(|rule| !is_productive(&productive, rule))
+        .map(|rule| GrammarLint::Unproductive(rule.id))
+    );
+
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::{parser::{traits::ParserSymbolClass, rule::ParserRuleSet}, symbol::traits::SymbolDefinition};
+
+    use super::GrammarLint;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Class {
+        S,
+        A,
+        B,
+        C,
+        D,
+        LowerA,
+        LowerB,
+        LowerC,
+        LowerD,
+        EOS
+    }
+
+    impl ParserSymbolClass for Class {
+        fn is_terminal(&self) -> bool {
+            matches!(self, Class::LowerA | Class::LowerB | Class::LowerC | Class::LowerD | Class::EOS)
+        }
+
+        fn eos() -> Self {
+            Self::EOS
+        }
+    }
+
+    struct SymDef;
+
+    impl SymbolDefinition for SymDef {
+        type Class = Class;
+        type Value = ();
+    }
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .add(Class::S, [Class::A, Class::B], &|_| Ok(())) // S → A B
+        .add(Class::S, [Class::D], &|_| Ok(())) // S → D
+        .add(Class::A, [Class::LowerA, Class::A], &|_| Ok(())) // A → a A
+        .add(Class::A, [] as [Class; 0], &|_| Ok(())) // A → ε
+        .add(Class::B, [Class::LowerB], &|_| Ok(())) // B → b
+        .add(Class::C, [Class::LowerC], &|_| Ok(())) // C → c
+        .add(Class::D, [Class::D, Class::LowerD], &|_| Ok(())) // D → D d
+        .add(Class::LowerA, [Class::LowerB], &|_| Ok(())) // a → b
+        .to_owned();
+    }
+
+    #[test]
+    fn first_follow_and_nullable_sets() {
+        let analysis = RULES.analysis();
+
+        assert!(analysis.is_nullable(&Class::A));
+        assert!(!analysis.is_nullable(&Class::S));
+        assert!(!analysis.is_nullable(&Class::LowerA));
+
+        assert_eq!(analysis.first(&Class::S), vec![Class::LowerA, Class::LowerB]);
+        assert_eq!(analysis.first(&Class::LowerD), vec![Class::LowerD]);
+        assert!(analysis.first(&Class::D).is_empty());
+
+        assert_eq!(analysis.follow(&Class::S), vec![Class::EOS]);
+        assert_eq!(analysis.follow(&Class::A), vec![Class::LowerB]);
+        assert_eq!(analysis.follow(&Class::B), vec![Class::EOS]);
+        assert_eq!(analysis.follow(&Class::D), vec![Class::EOS, Class::LowerD]);
+
+        assert_eq!(analysis.first_of(&[Class::A], &[Class::LowerC]), vec![Class::LowerA, Class::LowerC]);
+    }
+
+    #[test]
+    fn lint_grammar() {
+        let lints = RULES.lint();
+
+        assert_eq!(lints, vec![
+            GrammarLint::TerminalLhs(7),
+            GrammarLint::Unreachable(Class::C),
+            GrammarLint::Unproductive(1),
+            GrammarLint::Unproductive(6)
+        ]);
+
+        assert_eq!(lints[0].to_string(), "the left-hand side of the rule 7 is a terminal");
+    }
+}
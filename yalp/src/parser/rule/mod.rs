@@ -1,17 +1,34 @@
 pub mod runner;
+pub mod precedence;
+pub mod analysis;
+pub mod typed;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
-use crate::symbol::{traits::SymbolDefinition, Sym};
+use crate::{span::Span, symbol::{traits::SymbolDefinition, Sym}};
 
-use super::ParserError;
+use self::{analysis::{GrammarAnalysis, GrammarLint}, precedence::{Associativity, Precedence}};
+use super::{traits::ParserSymbolClass, ParserError};
 
 
 pub struct ParserRule<SymDef: SymbolDefinition> {
     pub id: usize,
     pub lhs: SymDef::Class,
     pub rhs: Vec<SymDef::Class>, 
-    pub reducer: runner::ParserRuleRunner<'static, Sym<SymDef>, ParserError>
+    /// Terminal whose precedence overrides the one of the rule (%prec)
+    pub prec: Option<SymDef::Class>,
+    /// The entry point started by the rule, if it is the synthetic start rule of one, see [ParserRuleSet::entry]
+    pub entry: Option<String>
+}
+
+impl<SymDef> ParserRule<SymDef> where SymDef: SymbolDefinition {
+    /// Returns the name of the left-hand side, `__Start_name` for the start rule of an entry point.
+    pub fn lhs_name(&self) -> String {
+        match &self.entry {
+            Some(name) => format!("__Start_{}", name),
+            None => format!("{:?}", self.lhs)
+        }
+    }
 }
 
 impl<SymDef> Debug for ParserRule<SymDef> 
@@ -22,13 +39,15 @@ where SymDef: SymbolDefinition
             .field("id", &self.id)
             .field("lhs", &self.lhs)
             .field("rhs", &self.rhs)
+            .field("prec", &self.prec)
+            .field("entry", &self.entry)
         .finish()
     }
 }
 
 impl<SymDef> Clone for ParserRule<SymDef> where SymDef: SymbolDefinition {
     fn clone(&self) -> Self {
-        Self { id: self.id.clone(), lhs: self.lhs.clone(), rhs: self.rhs.clone(), reducer: self.reducer.clone() }
+        Self { id: self.id.clone(), lhs: self.lhs.clone(), rhs: self.rhs.clone(), prec: self.prec.clone(), entry: self.entry.clone() }
     }
 }
 
@@ -38,48 +57,351 @@ impl<SymDef> PartialEq for ParserRule<SymDef> where SymDef: SymbolDefinition + '
     }
 }
 
-impl<SymDef> ParserRule<SymDef> where SymDef: SymbolDefinition + 'static {
-    pub fn execute(&self, syms: Vec<Sym<SymDef>>) -> Result<SymDef::Value, ParserError> {
-        self.reducer.execute(syms)
-    }
-}
+/// Build the value of the error symbol from the syntax error it stands for.
+pub type ErrorValueFunc<SymDef> = &'static (dyn Fn(&ParserError) -> <SymDef as SymbolDefinition>::Value + Sync + Send);
 
-pub struct ParserRuleSet<SymDef>(Vec<ParserRule<SymDef>>) where SymDef: SymbolDefinition + 'static;
+/// The rules of a grammar, and their reducers.
+/// 
+/// The reducers may receive a context, passed to the parser along with the tokens,
+/// so that each parse carries its own state while the rule set is shared, see [ParserRuleSet::add_with_context].
+pub struct ParserRuleSet<SymDef, Ctx = ()> where SymDef: SymbolDefinition + 'static {
+    rules: Vec<ParserRule<SymDef>>,
+    /// The reducer of each rule, by its id
+    reducers: Vec<runner::ParserRuleRunner<'static, Sym<SymDef>, ParserError, Ctx>>,
+    precedences: Vec<(SymDef::Class, Precedence)>,
+    error: Option<(SymDef::Class, ErrorValueFunc<SymDef>)>,
+    /// The synthetic start rule of each entry point, numbered after the rules
+    entries: Vec<ParserRule<SymDef>>
+}
 
-impl<SymDef> Clone for ParserRuleSet<SymDef> where SymDef: SymbolDefinition {
+impl<SymDef, Ctx> Clone for ParserRuleSet<SymDef, Ctx> where SymDef: SymbolDefinition {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        Self { rules: self.rules.clone(), reducers: self.reducers.clone(), precedences: self.precedences.clone(), error: self.error.clone(), entries: self.entries.clone() }
     }
 }
 
-impl<SymDef> ParserRuleSet<SymDef> where SymDef: SymbolDefinition {
+impl<SymDef, Ctx> ParserRuleSet<SymDef, Ctx> where SymDef: SymbolDefinition {
     pub fn new() -> Self {
-        Self(vec![])
+        Self { rules: vec![], reducers: vec![], precedences: vec![], error: None, entries: vec![] }
     }
 
     pub fn root(&self) -> Option<&ParserRule<SymDef>> {
-        self.0.first()
+        self.rules.first()
     }
 
+    /// Declare an entry point, the parsing may then start from the non-terminal instead of the root.
+    /// 
+    /// The entry point gets the synthetic start rule `__Start_name → lhs`, the input is accepted once it is reduced,
+    /// so the non-terminal may appear in other rules.
+    pub fn entry(&mut self, name: impl Into<String>, lhs: SymDef::Class) -> &mut Self {
+        let id = self.rules.len() + self.entries.len();
+        self.entries.push(ParserRule { id, lhs: lhs.clone(), rhs: vec![lhs], prec: None, entry: Some(name.into()) });
+        self
+    }
+
+    /// Returns the start rule of the entry point.
+    pub fn get_entry(&self, name: &str) -> Option<&ParserRule<SymDef>> {
+        self.entries
+            .iter()
+            .find(|rule| rule.entry.as_deref() == Some(name))
+    }
+
+    /// Iterate over the start rules of the entry points.
+    pub fn iter_entries(&self) -> impl Iterator<Item=&ParserRule<SymDef>> {
+        self.entries.iter()
+    }
+
+    /// Returns the root, then the start rules of the entry points.
+    /// 
+    /// A LR table has a start state for each of them, in this order.
+    pub fn starts(&self) -> Vec<&ParserRule<SymDef>> {
+        self.root().into_iter().chain(self.iter_entries()).collect()
+    }
+
+    /// Returns the rule, or the start rule of an entry point.
     pub fn get(&self, rule_id: usize) -> Option<&ParserRule<SymDef>> {
-        self.0.get(rule_id)
+        self.rules
+            .get(rule_id)
+            .or_else(|| rule_id.checked_sub(self.rules.len()).and_then(|index| self.entries.get(index)))
+    }
+
+    /// Iterate over the rules, the start rules of the entry points excluded
+    pub fn iter(&self) -> impl std::iter::Iterator<Item=&ParserRule<SymDef>> {
+        self.rules.iter()
+    }
+
+    /// Declare left-associative terminals (%left).
+    /// 
+    /// Each declaration binds tighter than the previous ones.
+    pub fn left<It: IntoIterator<Item=SymDef::Class>>(&mut self, terminals: It) -> &mut Self {
+        self.declare_precedence(terminals, Associativity::Left)
+    }
+
+    /// Declare right-associative terminals (%right).
+    /// 
+    /// Each declaration binds tighter than the previous ones.
+    pub fn right<It: IntoIterator<Item=SymDef::Class>>(&mut self, terminals: It) -> &mut Self {
+        self.declare_precedence(terminals, Associativity::Right)
+    }
+
+    /// Declare non-associative terminals (%nonassoc), chaining them is a syntax error.
+    /// 
+    /// Each declaration binds tighter than the previous ones.
+    pub fn nonassoc<It: IntoIterator<Item=SymDef::Class>>(&mut self, terminals: It) -> &mut Self {
+        self.declare_precedence(terminals, Associativity::NonAssoc)
+    }
+
+    /// Give the last added rule the precedence of the terminal (%prec).
+    pub fn prec(&mut self, terminal: SymDef::Class) -> &mut Self {
+        if let Some(rule) = self.rules.last_mut() {
+            rule.prec = Some(terminal);
+        }
+        self
+    }
+
+    /// Declare the terminal standing for a syntax error in error productions, and how to build its value.
+    /// 
+    /// On a syntax error, the parser pops its stack until it can shift the error symbol,
+    /// then discards the tokens until one can follow it.
+    pub fn error_symbol<F: Fn(&ParserError) -> SymDef::Value + Sync + Send>(&mut self, terminal: SymDef::Class, value: &'static F) -> &mut Self {
+        self.error = Some((terminal, value));
+        self
+    }
+
+    /// Returns the error terminal, and the builder of its value, if declared.
+    pub fn get_error_symbol(&self) -> Option<(&SymDef::Class, ErrorValueFunc<SymDef>)> {
+        self.error.as_ref().map(|(class, value)| (class, *value))
+    }
+
+    /// Returns the precedence declared for the terminal
+    pub fn terminal_precedence(&self, terminal: &SymDef::Class) -> Option<Precedence> {
+        self.precedences
+        .iter()
+        .find(|(term, _)| term == terminal)
+        .map(|(_, prec)| *prec)
+    }
+
+    fn declare_precedence<It: IntoIterator<Item=SymDef::Class>>(&mut self, terminals: It, assoc: Associativity) -> &mut Self {
+        let level = self.precedences.iter().map(|(_, prec)| prec.level + 1).max().unwrap_or(0);
+        
+        terminals
+        .into_iter()
+        .for_each(|term| self.precedences.push((term, Precedence { level, assoc })));
+
+        self
     }
 
     /// Add a new rule
+    /// 
+    /// The right-hand side may be empty, the rule then derives the empty sequence.
     pub fn add<E: Into<SymDef::Class>, It: IntoIterator<Item=E>, F: Fn(Vec<Sym<SymDef>>) -> Result<SymDef::Value, ParserError> + Sync + Send>(&mut self, lhs: SymDef::Class, rhs: It, reducer: &'static F) -> &mut Self {
-        let id = self.0.len();
-        self.0.push(
-            ParserRule {
-                id,
-                lhs,
-                rhs: rhs.into_iter().map(|t| t.into()).collect(),
-                reducer: runner::ParserRuleRunner(reducer)
-            }
-        );
+        self.push(
+            lhs,
+            rhs.into_iter().map(|t| t.into()).collect(),
+            runner::ParserRuleRunner(Arc::new(move |syms, _| reducer(syms)))
+        )
+    }
+
+    /// Add a new rule, whose reducer receives the context of the parse.
+    pub fn add_with_context<E: Into<SymDef::Class>, It: IntoIterator<Item=E>, F: Fn(Vec<Sym<SymDef>>, &mut Ctx) -> Result<SymDef::Value, ParserError> + Sync + Send>(&mut self, lhs: SymDef::Class, rhs: It, reducer: &'static F) -> &mut Self {
+        self.push(
+            lhs,
+            rhs.into_iter().map(|t| t.into()).collect(),
+            runner::ParserRuleRunner(Arc::new(reducer))
+        )
+    }
+
+    fn push(&mut self, lhs: SymDef::Class, rhs: Vec<SymDef::Class>, reducer: runner::ParserRuleRunner<'static, Sym<SymDef>, ParserError, Ctx>) -> &mut Self {
+        let id = self.rules.len();
+        self.rules.push(ParserRule { id, lhs, rhs, prec: None, entry: None });
+        self.reducers.push(reducer);
+
+        // The start rules of the entry points are numbered after the rules.
+        for (index, rule) in self.entries.iter_mut().enumerate() {
+            rule.id = id + 1 + index;
+        }
+
         self
     }
 
+    /// Execute the reducer of the rule, within the context of the parse.
+    /// 
+    /// The start rule of an entry point passes the value of its non-terminal through.
+    pub fn execute(&self, rule: &ParserRule<SymDef>, mut syms: Vec<Sym<SymDef>>, ctx: &mut Ctx) -> Result<SymDef::Value, ParserError> {
+        if rule.entry.is_some() && syms.len() == 1 {
+            return Ok(syms.remove(0).value);
+        }
+
+        let reducer = self.reducers
+            .get(rule.id)
+            .ok_or_else(|| ParserError::corrupt_table(syms.first().map(|sym| sym.span.clone()).unwrap_or(Span::from(0)), format!("missing reducer of the rule {}", rule.id)))?;
+
+        reducer.execute(syms, ctx)
+    }
+
+    /// Returns the same grammar over another definition of the symbols, each rule being reduced by the given reducer.
+    pub(crate) fn with_reducer<Other>(&self, reducer: runner::ParserRuleRunner<'static, Sym<Other>, ParserError>, error: ErrorValueFunc<Other>) -> ParserRuleSet<Other>
+    where Other: SymbolDefinition<Class = SymDef::Class>
+    {
+        let cast = |rule: &ParserRule<SymDef>| ParserRule { id: rule.id, lhs: rule.lhs.clone(), rhs: rule.rhs.clone(), prec: rule.prec.clone(), entry: rule.entry.clone() };
+
+        ParserRuleSet {
+            rules: self.rules.iter().map(cast).collect(),
+            reducers: self.rules.iter().map(|_| reducer.clone()).collect(),
+            precedences: self.precedences.clone(),
+            error: self.error.as_ref().map(|(class, _)| (class.clone(), error)),
+            entries: self.entries.iter().map(cast).collect()
+        }
+    }
+
     pub fn iter_by_lhs<'a>(&'a self, lhs: SymDef::Class) -> impl std::iter::Iterator<Item=&'a ParserRule<SymDef>> {
-        self.0.iter().filter(move |r| r.lhs == lhs)
+        self.rules.iter().filter(move |r| r.lhs == lhs)
+    }
+}
+
+impl<SymDef, Ctx> ParserRuleSet<SymDef, Ctx> where SymDef: SymbolDefinition, SymDef::Class: ParserSymbolClass {
+    /// Returns the precedence of the rule
+    /// 
+    /// It is the one of its %prec terminal if any, otherwise the one of its last terminal.
+    pub fn rule_precedence(&self, rule: &ParserRule<SymDef>) -> Option<Precedence> {
+        match &rule.prec {
+            Some(term) => self.terminal_precedence(term),
+            None => rule.rhs
+                .iter()
+                .rev()
+                .find(|sym| sym.is_terminal())
+                .and_then(|term| self.terminal_precedence(term))
+        }
+    }
+
+    /// Returns every symbol of the grammar, the end of stream first, then in order of appearance.
+    /// 
+    /// A symbol's position in this list is its index in the compiled tables.
+    pub fn symbols(&self) -> Vec<SymDef::Class> {
+        let mut symbols = vec![SymDef::Class::eos()];
+
+        let error = self.error.iter().map(|(class, _)| class);
+        let rules = self.rules.iter().flat_map(|rule| std::iter::once(&rule.lhs).chain(rule.rhs.iter()));
+
+        for sym in error.chain(rules) {
+            if !symbols.contains(sym) {
+                symbols.push(sym.clone());
+            }
+        }
+
+        symbols
+    }
+
+    /// Returns a hash of the rules, the precedences and the error symbol, stable across builds.
+    /// 
+    /// Tables compiled ahead of time are checked against it, to ensure they were generated from the same grammar.
+    pub fn fingerprint(&self) -> u64 {
+        // FNV-1a, the std hasher is not guaranteed to be stable across releases.
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut write = |repr: String| {
+            for byte in repr.bytes().chain([0xff]) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+
+        for rule in self.rules.iter() {
+            write(format!("{:?} -> {:?} %prec {:?}", rule.lhs, rule.rhs, rule.prec));
+        }
+
+        for (term, prec) in self.precedences.iter() {
+            write(format!("{:?} {:?}", term, prec));
+        }
+
+        write(format!("error {:?}", self.error.as_ref().map(|(class, _)| class)));
+
+        for rule in self.entries.iter() {
+            write(format!("entry {} {:?}", rule.lhs_name(), rule.lhs));
+        }
+
+        hash
+    }
+
+    /// Compute the FIRST, FOLLOW and nullable sets of the grammar.
+    pub fn analysis(&self) -> GrammarAnalysis<SymDef> {
+        GrammarAnalysis::build(self)
+    }
+
+    /// Check the grammar for unreachable non-terminals, unproductive rules, and rules whose left-hand side is a terminal.
+    /// 
+    /// Such rules are accepted by the table generator, but are most likely mistakes.
+    pub fn lint(&self) -> Vec<GrammarLint<SymDef>> {
+        analysis::lint(self)
     }
 }   
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::parser::{earley::EarleyParser, fixtures::{first, sum, tokens, Class, SymDef}, lr::LrParser, traits::Parser};
+
+    use super::ParserRuleSet;
+
+    /// Interns the identifiers, and collects the warnings.
+    #[derive(Default)]
+    struct Interner {
+        names: Vec<i64>,
+        warnings: Vec<String>
+    }
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef, Interner> = ParserRuleSet::new()
+        .add(Class::S, [Class::List], &first) // S → List
+        .add(Class::List, [Class::List, Class::Num], &sum) // List → List num
+        .add_with_context(Class::List, [] as [Class; 0], &|_, ctx: &mut Interner| { // List → ε
+            ctx.warnings.push("empty list".to_string());
+            Ok(0)
+        })
+        .to_owned();
+
+        static ref INTERNING_RULES: ParserRuleSet<SymDef, Interner> = ParserRuleSet::new()
+        .add(Class::S, [Class::List], &first) // S → List
+        .add_with_context(Class::List, [Class::List, Class::Num], &|syms, ctx: &mut Interner| { // List → List num
+            let index = match ctx.names.iter().position(|name| *name == syms[1].value) {
+                Some(index) => index,
+                None => {
+                    ctx.names.push(syms[1].value);
+                    ctx.names.len() - 1
+                }
+            };
+            Ok(syms[0].value * 10 + index as i64)
+        })
+        .add(Class::List, [] as [Class; 0], &|_| Ok(0)) // List → ε
+        .to_owned();
+    }
+
+    /// The identifiers, numbered by their values.
+    fn idents(input: &[i64]) -> Vec<(Class, i64)> {
+        input.iter().map(|value| (Class::Num, *value)).collect()
+    }
+
+    #[test]
+    fn reducers_receive_the_context() {
+        let lr = LrParser::generate(&INTERNING_RULES).unwrap();
+        let earley = EarleyParser::generate(&INTERNING_RULES).unwrap();
+
+        let mut ctx = Interner::default();
+        assert_eq!(lr.parse_with_context::<i64, _, _, _>(tokens(&idents(&[7, 8, 7])), &mut ctx).unwrap(), 10);
+        assert_eq!(ctx.names, vec![7, 8]);
+
+        // Each parse carries its own context.
+        let mut other = Interner::default();
+        assert_eq!(earley.parse_with_context::<i64, _, _, _>(tokens(&idents(&[8, 7])), &mut other).unwrap(), 1);
+        assert_eq!(other.names, vec![8, 7]);
+        assert_eq!(ctx.names, vec![7, 8]);
+
+        // The context is defaulted when none is given.
+        assert_eq!(lr.parse::<i64, _, _, _>(tokens(&idents(&[5, 5]))).unwrap(), 0);
+
+        let parser = LrParser::generate(&RULES).unwrap();
+        let mut ctx = Interner::default();
+        parser.parse_with_context::<i64, _, _, _>(tokens(&[]), &mut ctx).unwrap();
+        assert_eq!(ctx.warnings, vec!["empty list".to_string()]);
+    }
+}
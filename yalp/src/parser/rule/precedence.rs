@@ -0,0 +1,41 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+    NonAssoc
+}
+
+/// The binding strength of a terminal, the higher the level, the tighter it binds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Precedence {
+    pub level: usize,
+    pub assoc: Associativity
+}
+
+/// The outcome of a shift/reduce conflict resolved by precedence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Shift,
+    Reduce,
+    /// Neither action is allowed, the terminal is a syntax error.
+    Error
+}
+
+impl Precedence {
+    /// Resolve a shift/reduce conflict between a rule with this precedence, and the lookahead terminal.
+    pub fn resolve(&self, lookahead: &Precedence) -> Resolution {
+        if self.level > lookahead.level {
+            return Resolution::Reduce;
+        }
+
+        if self.level < lookahead.level {
+            return Resolution::Shift;
+        }
+
+        match self.assoc {
+            Associativity::Left => Resolution::Reduce,
+            Associativity::Right => Resolution::Shift,
+            Associativity::NonAssoc => Resolution::Error
+        }
+    }
+}
@@ -1,32 +1,45 @@
+use std::sync::Arc;
+
 use crate::symbol::traits::{SymbolDefinition, Symbol};
 
-type AnyRuleRunnerFunc<'a, Sym, E> = &'a (dyn Fn(Vec<Sym>) -> Result<<Sym as SymbolDefinition>::Value, E> + Sync + Send);
+type AnyRuleRunnerFunc<'a, Sym, E, Ctx> = Arc<dyn Fn(Vec<Sym>, &mut Ctx) -> Result<<Sym as SymbolDefinition>::Value, E> + Sync + Send + 'a>;
+
+/// A reducer, receiving the context of the parse along with the symbols.
+pub struct ParserRuleRunner<'a, SymDef, E, Ctx = ()>(pub AnyRuleRunnerFunc<'a, SymDef, E, Ctx>) where SymDef: SymbolDefinition;
 
-#[derive(Clone)]
-pub struct ParserRuleRunner<'a, SymDef, E>(pub AnyRuleRunnerFunc<'a, SymDef, E>) where SymDef: SymbolDefinition;
+impl<'a, Sym, E, Ctx> Clone for ParserRuleRunner<'a, Sym, E, Ctx> where Sym: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
 
-impl<'a, Sym, E> ParserRuleRunner<'a, Sym, E> where Sym: Symbol 
+impl<'a, Sym, E, Ctx> ParserRuleRunner<'a, Sym, E, Ctx> where Sym: Symbol 
 {
-    pub fn execute(&self, symbols: Vec<Sym>) -> Result<Sym::Value, E> {
-        self.0(symbols)
+    pub fn execute(&self, symbols: Vec<Sym>, ctx: &mut Ctx) -> Result<Sym::Value, E> {
+        self.0(symbols, ctx)
     }
 }
 
-#[derive(Clone)]
-pub struct ParserRulesRunners<'a, SymDef, E>(Vec<ParserRuleRunner<'a, SymDef, E>>) where SymDef: SymbolDefinition;  
+pub struct ParserRulesRunners<'a, SymDef, E, Ctx = ()>(Vec<ParserRuleRunner<'a, SymDef, E, Ctx>>) where SymDef: SymbolDefinition;  
+
+impl<'a, Sym, E, Ctx> Clone for ParserRulesRunners<'a, Sym, E, Ctx> where Sym: SymbolDefinition {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
 
-impl<'a, Sym, E> ParserRulesRunners<'a, Sym, E> where Sym: Symbol
+impl<'a, Sym, E, Ctx> ParserRulesRunners<'a, Sym, E, Ctx> where Sym: Symbol
 {    
     pub fn new() -> Self {
         Self(vec![])
     }
     
-    pub fn add<F: Fn(Vec<Sym>) -> Result<Sym::Value, E> + Sync + Send>(&mut self, f: &'a F) -> &mut Self {
-        self.0.push(ParserRuleRunner(f));
+    pub fn add<F: Fn(Vec<Sym>, &mut Ctx) -> Result<Sym::Value, E> + Sync + Send>(&mut self, f: &'a F) -> &mut Self {
+        self.0.push(ParserRuleRunner(Arc::new(f)));
         self
     }
     
-    pub fn get(&self, rule: usize) -> Option<&ParserRuleRunner<'_, Sym, E>> {
+    pub fn get(&self, rule: usize) -> Option<&ParserRuleRunner<'_, Sym, E, Ctx>> {
         self.0.get(rule)
     }
 }
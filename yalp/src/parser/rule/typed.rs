@@ -0,0 +1,239 @@
+//! Rules whose reducers receive and return typed values
+//!
+//! Each symbol is bound to the Rust type of its value by a marker implementing [TypedSymbol],
+//! the values are converted from and to [SymbolDefinition::Value] around the reducer,
+//! so a reducer not matching the types of its rule fails to compile.
+//! The types are `Clone`, as a value may be shared by the stacks the parser saves.
+//!
+//! ```ignore
+//! struct Expr;
+//!
+//! impl TypedSymbol<SymDef> for Expr {
+//!     type Type = i64;
+//!
+//!     fn class() -> Class {
+//!         Class::Expr
+//!     }
+//! }
+//!
+//! rules.typed(Expr, (Expr, Plus, Num), |(lhs, _, rhs)| Ok(lhs + rhs));
+//! ```
+use std::{any::Any, sync::Arc};
+
+use crate::{parser::ParserError, span::Span, symbol::{traits::SymbolDefinition, AnyValue, Sym}};
+
+use super::{runner::ParserRuleRunner, ParserRuleSet};
+
+/// A symbol of the grammar, bound to the type of its value.
+pub trait TypedSymbol<SymDef: SymbolDefinition> {
+    type Type;
+
+    fn class() -> SymDef::Class;
+}
+
+/// Symbol values which can hold a value of the type.
+pub trait TypedValue<T>: Sized {
+    fn from_typed(value: T) -> Self;
+
+    /// Take the typed value out, returns the value as is if it holds another type.
+    fn into_typed(self) -> Result<T, Self>;
+}
+
+impl<T: Any + Clone> TypedValue<T> for AnyValue {
+    fn from_typed(value: T) -> Self {
+        AnyValue::new(value)
+    }
+
+    fn into_typed(self) -> Result<T, Self> {
+        self.downcast()
+    }
+}
+
+/// A right-hand side of typed symbols, written as a tuple.
+pub trait TypedRhs<SymDef: SymbolDefinition> {
+    /// The tuple of the values of the symbols
+    type Args;
+
+    fn classes() -> Vec<SymDef::Class>;
+
+    fn extract(syms: Vec<Sym<SymDef>>) -> Result<Self::Args, ParserError>;
+}
+
+impl<SymDef> TypedRhs<SymDef> for () where SymDef: SymbolDefinition {
+    type Args = ();
+
+    fn classes() -> Vec<SymDef::Class> {
+        vec![]
+    }
+
+    fn extract(_: Vec<Sym<SymDef>>) -> Result<Self::Args, ParserError> {
+        Ok(())
+    }
+}
+
+/// Take the typed value of the symbol.
+fn take<SymDef, S>(sym: Option<Sym<SymDef>>) -> Result<S::Type, ParserError>
+where SymDef: SymbolDefinition, S: TypedSymbol<SymDef>, SymDef::Value: TypedValue<S::Type>
+{
+    let Some(Sym { span, class, value }) = sym else {
+        return Err(ParserError::corrupt_table(Span::from(0), "missing symbol to reduce"));
+    };
+
+    value
+    .into_typed()
+    .map_err(|value| ParserError::wrong_value(Sym::<SymDef> { span, class, value }))
+}
+
+macro_rules! impl_typed_rhs {
+    ($($sym:ident),+) => {
+        impl<SymDef, $($sym),+> TypedRhs<SymDef> for ($($sym,)+)
+        where SymDef: SymbolDefinition, $($sym: TypedSymbol<SymDef>, SymDef::Value: TypedValue<$sym::Type>),+
+        {
+            type Args = ($(<$sym as TypedSymbol<SymDef>>::Type,)+);
+
+            fn classes() -> Vec<SymDef::Class> {
+                vec![$(<$sym as TypedSymbol<SymDef>>::class()),+]
+            }
+
+            #[allow(non_snake_case)]
+            fn extract(syms: Vec<Sym<SymDef>>) -> Result<Self::Args, ParserError> {
+                let mut syms = syms.into_iter();
+                $(let $sym = take::<SymDef, $sym>(syms.next())?;)+
+                Ok(($($sym,)+))
+            }
+        }
+    };
+}
+
+impl_typed_rhs!(A);
+impl_typed_rhs!(A, B);
+impl_typed_rhs!(A, B, C);
+impl_typed_rhs!(A, B, C, D);
+impl_typed_rhs!(A, B, C, D, E);
+impl_typed_rhs!(A, B, C, D, E, F);
+impl_typed_rhs!(A, B, C, D, E, F, G);
+impl_typed_rhs!(A, B, C, D, E, F, G, H);
+
+impl<SymDef, Ctx> ParserRuleSet<SymDef, Ctx> where SymDef: SymbolDefinition {
+    /// Add a new rule, whose reducer receives the values of the right-hand side as a tuple, and returns the one of the left-hand side.
+    ///
+    /// The symbols are given by their markers, the right-hand side being a tuple of up to 8 of them.
+    pub fn typed<Lhs, Rhs, F>(&mut self, _lhs: Lhs, _rhs: Rhs, reducer: F) -> &mut Self
+    where Lhs: TypedSymbol<SymDef>,
+            Rhs: TypedRhs<SymDef>,
+            SymDef::Value: TypedValue<Lhs::Type>,
+            F: Fn(Rhs::Args) -> Result<Lhs::Type, ParserError> + Sync + Send + 'static
+    {
+        self.push(
+            Lhs::class(),
+            Rhs::classes(),
+            ParserRuleRunner(Arc::new(move |syms, _| reducer(Rhs::extract(syms)?).map(SymDef::Value::from_typed)))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use crate::{parser::{fixtures::Class, lr::{session::LrParserStatus, LrParser}, rule::ParserRuleSet, traits::Parser, ParserError, ParserErrorKind}, symbol::{traits::SymbolDefinition, AnyValue, Sym}};
+
+    use super::{TypedRhs, TypedSymbol};
+
+    struct SymDef;
+
+    impl SymbolDefinition for SymDef {
+        type Class = Class;
+        type Value = AnyValue;
+    }
+
+    macro_rules! typed_symbol {
+        ($marker:ident: $ty:ty) => {
+            struct $marker;
+
+            impl TypedSymbol<SymDef> for $marker {
+                type Type = $ty;
+
+                fn class() -> Class {
+                    Class::$marker
+                }
+            }
+        };
+    }
+
+    typed_symbol!(Root: i64);
+    typed_symbol!(E: i64);
+    typed_symbol!(Num: i64);
+    typed_symbol!(Plus: ());
+
+    lazy_static! {
+        static ref RULES: ParserRuleSet<SymDef> = ParserRuleSet::new()
+        .typed(Root, (E,), |(expr,)| Ok(expr))
+        .typed(E, (E, Plus, Num), |(lhs, _, rhs)| Ok(lhs + rhs))
+        .typed(E, (Num,), |(num,)| Ok(num))
+        .to_owned();
+    }
+
+    fn tokens(input: Vec<(Class, AnyValue)>) -> impl Iterator<Item=Result<Sym<SymDef>, ParserError>> {
+        let len = input.len();
+
+        input
+        .into_iter()
+        .enumerate()
+        .map(|(i, (class, value))| Ok(Sym::new(i..i + 1, class, value)))
+        .chain(std::iter::once(Ok(Sym::new(len, Class::EOS, AnyValue::new(())))))
+    }
+
+    #[test]
+    fn reduce_typed_values() {
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        let value: AnyValue = parser.parse(tokens(vec![
+            (Class::Num, AnyValue::new(1_i64)),
+            (Class::Plus, AnyValue::new(())),
+            (Class::Num, AnyValue::new(2_i64))
+        ])).unwrap();
+
+        assert_eq!(value.downcast::<i64>().unwrap(), 3);
+    }
+
+    #[test]
+    fn reject_wrong_token_values() {
+        let parser = LrParser::generate(&RULES).unwrap();
+
+        let errors = parser.parse::<AnyValue, _, _, _>(tokens(vec![
+            (Class::Num, AnyValue::new("1"))
+        ])).unwrap_err();
+
+        assert!(errors[0].message.starts_with("unexpecting value AnyValue(\"&str\")"), "{}", errors[0].message);
+    }
+
+    #[test]
+    fn reduce_shared_values() {
+        let parser = LrParser::generate(&RULES).unwrap();
+        let input = || tokens(vec![
+            (Class::Num, AnyValue::new(1_i64)),
+            (Class::Plus, AnyValue::new(())),
+            (Class::Num, AnyValue::new(2_i64))
+        ]);
+
+        // The saved stacks share the values with the parser.
+        let (_, value) = parser.parse_incremental::<AnyValue>(input().map(Result::unwrap).collect());
+        assert_eq!(value.unwrap().downcast::<i64>().unwrap(), 3);
+
+        let mut session = parser.session();
+        for tok in input() {
+            assert!(!matches!(session.feed(tok.unwrap()), LrParserStatus::Error(_)));
+        }
+
+        let other = session.clone();
+        assert_eq!(other.finish::<AnyValue>().unwrap().downcast::<i64>().unwrap(), 3);
+        assert_eq!(session.finish::<AnyValue>().unwrap().downcast::<i64>().unwrap(), 3);
+    }
+
+    #[test]
+    fn reject_missing_symbols() {
+        let error = <(E, Plus, Num) as TypedRhs<SymDef>>::extract(vec![Sym::new(0, Class::E, AnyValue::new(1_i64))]).err().unwrap();
+        assert_eq!(error.kind, ParserErrorKind::CorruptTable);
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::{Display, Write};
+
+use crate::span::Span;
+
+/// A position within a source file, starting at 1:1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    /// Counted in characters
+    pub column: usize
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A source text, and where its lines start.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    name: String,
+    src: String,
+    /// Offset of the file within the source map
+    start: usize,
+    /// Offsets of the lines, relative to the start of the file
+    lines: Vec<usize>
+}
+
+impl SourceFile {
+    pub fn new(name: &str, src: &str) -> Self {
+        Self::new_at(name, src, 0)
+    }
+
+    fn new_at(name: &str, src: &str, start: usize) -> Self {
+        let lines = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self { name: name.to_string(), src: src.to_string(), start, lines }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    /// The offset of the first byte of the file, the spans of its tokens are shifted by it.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The offset past the last byte of the file
+    pub fn end(&self) -> usize {
+        self.start + self.src.len()
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset <= self.end()
+    }
+
+    /// Resolve the offset into a line and a column, the offset is clamped to the file.
+    pub fn location(&self, offset: usize) -> Location {
+        let offset = offset.clamp(self.start, self.end()) - self.start;
+        let line = self.lines.partition_point(|start| *start <= offset) - 1;
+        let column = self.src[self.lines[line]..offset].chars().count() + 1;
+
+        Location { line: line + 1, column }
+    }
+
+    /// Returns the line, without its line ending, starting at 1.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start = *self.lines.get(line.checked_sub(1)?)?;
+        let end = self.lines.get(line).copied().unwrap_or(self.src.len());
+
+        Some(self.src[start..end].trim_end_matches(['\n', '\r']))
+    }
+
+    /// Returns the text covered by the span
+    pub fn slice(&self, span: &Span) -> Option<&str> {
+        match span {
+            Span::Interval { from, to } if self.contains(*from) && self.contains(*to) => {
+                self.src.get(from - self.start..to - self.start)
+            },
+            _ => None
+        }
+    }
+}
+
+/// The source files of a parsing, laid out one after the other so each offset belongs to a single file.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, its offsets start past the ones of the previous file.
+    pub fn add(&mut self, name: &str, src: &str) -> &SourceFile {
+        // Leave a gap so that the end of a file does not overlap the start of the next one.
+        let start = self.files.last().map(|file| file.end() + 1).unwrap_or(0);
+        self.files.push(SourceFile::new_at(name, src, start));
+        self.files.last().unwrap()
+    }
+
+    /// Iterate over the files
+    pub fn iter(&self) -> impl Iterator<Item=&SourceFile> {
+        self.files.iter()
+    }
+
+    /// Returns the file containing the offset
+    pub fn lookup(&self, offset: usize) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.contains(offset))
+    }
+
+    /// Resolve the offset into its file and location
+    pub fn location(&self, offset: usize) -> Option<(&SourceFile, Location)> {
+        self.lookup(offset).map(|file| (file, file.location(offset)))
+    }
+
+    /// Render a rustc-style diagnostic, underlining the span within its source line.
+    ///
+    /// ```text
+    /// error: unexpected token
+    ///  --> main.txt:2:9
+    ///   |
+    /// 2 | let x = = 1;
+    ///   |         ^
+    /// ```
+    pub fn snippet(&self, span: &Span, message: &str) -> String {
+        let mut out = format!("error: {}", message);
+
+        // Proc-macro spans have no location within the sources.
+        let Span::Interval { from, to } = span else {
+            return out;
+        };
+
+        let Some((file, start)) = self.location(*from) else {
+            return out;
+        };
+
+        let end = file.location(*to);
+        let line = file.line(start.line).unwrap_or_default();
+        let gutter = " ".repeat(start.line.to_string().len());
+
+        // Underline up to the end of the first line, and at least one character.
+        let width = if end.line == start.line { end.column - start.column } else { line.chars().count() + 1 - start.column };
+        let indent = line
+            .chars()
+            .take(start.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        write!(out, "\n{gutter}--> {}:{}", file.name(), start).unwrap();
+        write!(out, "\n{gutter} |").unwrap();
+        write!(out, "\n{} | {}", start.line, line).unwrap();
+        write!(out, "\n{gutter} | {}{}", indent, "^".repeat(width.max(1))).unwrap();
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::span::Span;
+
+    use super::{Location, SourceMap};
+
+    #[test]
+    fn resolve_locations() {
+        let mut sources = SourceMap::new();
+        let first = sources.add("first.txt", "ab\ncdé\n").start();
+        let second = sources.add("second.txt", "x\r\ny").start();
+
+        assert_eq!(sources.location(first + 4).map(|(f, l)| (f.name(), l)), Some(("first.txt", Location { line: 2, column: 2 })));
+        assert_eq!(sources.location(first + 7).unwrap().1, Location { line: 2, column: 4 });
+        assert_eq!(sources.location(second + 3).map(|(f, l)| (f.name(), l)), Some(("second.txt", Location { line: 2, column: 1 })));
+        assert_eq!(sources.lookup(second).unwrap().line(1), Some("x"));
+        assert_eq!(sources.lookup(second).unwrap().slice(&Span::Interval { from: second, to: second + 1 }), Some("x"));
+    }
+
+    #[test]
+    fn render_snippet() {
+        let mut sources = SourceMap::new();
+        sources.add("main.txt", "let a = 1;\nlet x = = 1;\n");
+
+        let snippet = sources.snippet(&Span::Interval { from: 19, to: 20 }, "unexpected token");
+
+        assert_eq!(snippet, "error: unexpected token\n --> main.txt:2:9\n  |\n2 | let x = = 1;\n  |         ^");
+    }
+
+    #[test]
+    fn join_spans() {
+        let span = Span::Interval { from: 4, to: 6 }.join(&Span::Interval { from: 1, to: 2 });
+        assert!(matches!(span, Span::Interval { from: 1, to: 6 }));
+    }
+}
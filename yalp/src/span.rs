@@ -1,21 +1,80 @@
 #[derive(Clone, Debug)]
 pub enum Span {
-    ProcMacroSpan(proc_macro2::Span),
+    /// A location within the invocation of a macro
+    ProcMacroSpan(ProcMacroSpan),
+    /// Byte offsets, the end excluded, see [crate::source::SourceMap] to resolve them.
     Interval{from: usize, to: usize}
 }
 
+impl Span {
+    /// Returns the span covering both spans, and what lies between them.
+    /// 
+    /// Proc-macro spans can only be joined on nightly, the first span is kept otherwise.
+    pub fn join(&self, other: &Span) -> Span {
+        match (self, other) {
+            (Self::Interval { from, to }, Self::Interval { from: other_from, to: other_to }) => Self::Interval { 
+                from: *from.min(other_from), 
+                to: *to.max(other_to) 
+            },
+            (Self::ProcMacroSpan(span), Self::ProcMacroSpan(other)) => Self::ProcMacroSpan(span.join(*other)),
+            _ => self.clone()
+        }
+    }
+
+    /// Returns the empty span at the start of the span.
+    /// 
+    /// Proc-macro spans cannot be shrunk, and are kept as is.
+    pub fn shrink_to_lo(&self) -> Span {
+        match self {
+            Self::Interval { from, .. } => Self::Interval { from: *from, to: *from },
+            Self::ProcMacroSpan(span) => Self::ProcMacroSpan(*span)
+        }
+    }
+
+    /// Returns the empty span at the end of the span.
+    /// 
+    /// Proc-macro spans cannot be shrunk, and are kept as is.
+    pub fn shrink_to_hi(&self) -> Span {
+        match self {
+            Self::Interval { to, .. } => Self::Interval { from: *to, to: *to },
+            Self::ProcMacroSpan(span) => Self::ProcMacroSpan(*span)
+        }
+    }
+}
+
+/// A span of a macro invocation, only built and read with the proc-macro feature.
+///
+/// It is opaque without the feature, so that enabling it does not change the variants of [Span].
+#[derive(Clone, Copy, Debug)]
+pub struct ProcMacroSpan(#[cfg(feature = "proc-macro")] proc_macro2::Span);
+
+impl ProcMacroSpan {
+    #[cfg(feature = "proc-macro")]
+    fn join(self, other: Self) -> Self {
+        Self(self.0.join(other.0).unwrap_or(self.0))
+    }
+
+    #[cfg(not(feature = "proc-macro"))]
+    fn join(self, _: Self) -> Self {
+        self
+    }
+}
+
+#[cfg(feature = "proc-macro")]
 impl Into<proc_macro2::Span> for Span {
     fn into(self) -> proc_macro2::Span {
         match self {
-            Self::ProcMacroSpan(span) => span,
-            _ => unreachable!("not a macro span")
+            Self::ProcMacroSpan(span) => span.0,
+            // Offsets within a plain source have no location in the macro invocation.
+            Self::Interval { .. } => proc_macro2::Span::call_site()
         }
     }
 }
 
+#[cfg(feature = "proc-macro")]
 impl From<proc_macro2::Span> for Span {
     fn from(value: proc_macro2::Span) -> Self {
-        Self::ProcMacroSpan(value)
+        Self::ProcMacroSpan(ProcMacroSpan(value))
     }
 }
 
@@ -23,4 +82,10 @@ impl From<usize> for Span {
     fn from(value: usize) -> Self {
         Self::Interval{from: value, to: value}
     }
-}
\ No newline at end of file
+}
+
+impl From<std::ops::Range<usize>> for Span {
+    fn from(value: std::ops::Range<usize>) -> Self {
+        Self::Interval{from: value.start, to: value.end}
+    }
+}
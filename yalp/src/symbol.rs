@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{any::Any, fmt::Debug, rc::Rc};
 
 use crate::{span::Span, parser::ParserError};
 
@@ -83,3 +83,41 @@ impl<S: SymbolDefinition> traits::Symbol for Sym<S> {
         self.value.clone().try_into().map_err(|_| ParserError::wrong_value(self))
     }
 }
+
+/// A value of any type, to do without an enum gathering the types of every symbol.
+/// 
+/// See [crate::parser::rule::typed] to build rules whose reducers receive and return typed values.
+#[derive(Clone)]
+pub struct AnyValue {
+    value: Rc<dyn Any>,
+    type_name: &'static str
+}
+
+impl AnyValue {
+    pub fn new<T: Any>(value: T) -> Self {
+        Self { value: Rc::new(value), type_name: std::any::type_name::<T>() }
+    }
+
+    /// Returns true if the value is of the type
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+
+    /// Take the value out, fails if it is of another type.
+    /// 
+    /// The value is cloned if it is shared, such as by the stacks saved by the parser.
+    pub fn downcast<T: Any + Clone>(self) -> Result<T, Self> {
+        let type_name = self.type_name;
+
+        match self.value.downcast::<T>() {
+            Ok(value) => Ok(Rc::try_unwrap(value).unwrap_or_else(|value| T::clone(&value))),
+            Err(value) => Err(Self { value, type_name })
+        }
+    }
+}
+
+impl Debug for AnyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AnyValue").field(&self.type_name).finish()
+    }
+}
@@ -0,0 +1,54 @@
+//! The grammar and the tokens shared by the integration tests.
+
+use yalp::parser::ParserError;
+use yalp::symbol::Sym;
+
+/// A comma separated list of items, right-recursive so that it is LL(1) as well as LALR(1).
+pub mod list {
+    yalp::grammar! {
+        pub symbols ListDef { class: Class, value: Value }
+        pub rules RULES;
+
+        terminals {
+            EOS,
+            Comma,
+            Item: u32
+        }
+
+        nonterminals {
+            Root: Vec<u32>,
+            List: Vec<u32>,
+            Rest: Vec<u32>
+        }
+
+        eos EOS;
+
+        Root -> List => |list| Ok(list);
+        List -> Item Rest => |item, mut rest| {
+            rest.insert(0, item);
+            Ok(rest)
+        };
+        Rest -> Comma Item Rest => |_, item, mut rest| {
+            rest.insert(0, item);
+            Ok(rest)
+        };
+        Rest -> => || Ok(vec![]);
+    }
+}
+
+use list::{ListDef, Class, Value};
+
+/// The tokens of the items separated by commas, ended by the end of stream.
+pub fn tokens(items: &[u32]) -> impl Iterator<Item=Result<Sym<ListDef>, ParserError>> {
+    let mut toks = vec![];
+
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            toks.push(Sym::new(toks.len(), Class::Comma, Value::Comma));
+        }
+        toks.push(Sym::new(toks.len(), Class::Item, Value::Item(*item)));
+    }
+
+    toks.push(Sym::new(toks.len(), Class::EOS, Value::EOS));
+    toks.into_iter().map(Ok)
+}
@@ -0,0 +1,47 @@
+// Generated by `LlParser::to_rust_source`, do not edit.
+
+/// Parse the tokens, from the root rule.
+pub fn parse<I, Ctx>(rules: &::yalp::parser::rule::ParserRuleSet<super::ListDef, Ctx>, tokens: I, ctx: &mut Ctx) -> ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>
+where I: ::std::iter::Iterator<Item = ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>>
+{
+    let mut cursor = ::yalp::parser::ll::LlCursor::new(rules, tokens, ctx);
+    let cursor = &mut cursor;
+    // Root → List
+    let root = { let syms = ::std::vec![parse_list(cursor)?]; cursor.reduce(0, syms) }?;
+    cursor.finish(root)
+}
+
+/// Derive Root
+pub fn parse_root<I, Ctx>(cursor: &mut ::yalp::parser::ll::LlCursor<'_, super::ListDef, I, Ctx>) -> ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>
+where I: ::std::iter::Iterator<Item = ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>>
+{
+    match cursor.peek_class()? {
+        // Root → List
+        super::Class::Item => { let syms = ::std::vec![parse_list(cursor)?]; cursor.reduce(0, syms) },
+        _ => ::std::result::Result::Err(cursor.unexpected(::std::vec![super::Class::Item]))
+    }
+}
+
+/// Derive List
+pub fn parse_list<I, Ctx>(cursor: &mut ::yalp::parser::ll::LlCursor<'_, super::ListDef, I, Ctx>) -> ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>
+where I: ::std::iter::Iterator<Item = ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>>
+{
+    match cursor.peek_class()? {
+        // List → Item Rest
+        super::Class::Item => { let syms = ::std::vec![cursor.expect(super::Class::Item)?, parse_rest(cursor)?]; cursor.reduce(1, syms) },
+        _ => ::std::result::Result::Err(cursor.unexpected(::std::vec![super::Class::Item]))
+    }
+}
+
+/// Derive Rest
+pub fn parse_rest<I, Ctx>(cursor: &mut ::yalp::parser::ll::LlCursor<'_, super::ListDef, I, Ctx>) -> ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>
+where I: ::std::iter::Iterator<Item = ::std::result::Result<::yalp::symbol::Sym<super::ListDef>, ::yalp::parser::ParserError>>
+{
+    match cursor.peek_class()? {
+        // Rest → Comma Item Rest
+        super::Class::Comma => { let syms = ::std::vec![cursor.expect(super::Class::Comma)?, cursor.expect(super::Class::Item)?, parse_rest(cursor)?]; cursor.reduce(2, syms) },
+        // Rest → ε
+        super::Class::EOS => { let syms = ::std::vec![]; cursor.reduce(3, syms) },
+        _ => ::std::result::Result::Err(cursor.unexpected(::std::vec![super::Class::Comma, super::Class::EOS]))
+    }
+}
@@ -0,0 +1,33 @@
+::yalp::parser::lr::compiled::LrCompiledTable {
+    fingerprint: 0xe625ba22e6360f6d,
+    states: ::std::borrow::Cow::Borrowed(&[
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(3, ::yalp::parser::lr::action::LrParserOp::Shift(2))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(2, 1)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Accept)]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(5, ::yalp::parser::lr::action::LrParserOp::Shift(4)), (0, ::yalp::parser::lr::action::LrParserOp::Reduce(3))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(4, 3)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(1))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(3, ::yalp::parser::lr::action::LrParserOp::Shift(5))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(5, ::yalp::parser::lr::action::LrParserOp::Shift(4)), (0, ::yalp::parser::lr::action::LrParserOp::Reduce(3))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[(4, 6)])
+        },
+        ::yalp::parser::lr::compiled::LrCompiledState {
+            actions: ::std::borrow::Cow::Borrowed(&[(0, ::yalp::parser::lr::action::LrParserOp::Reduce(2))]),
+            gotos: ::std::borrow::Cow::Borrowed(&[])
+        },
+    ])
+}
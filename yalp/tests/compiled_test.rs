@@ -0,0 +1,33 @@
+use yalp::parser::{lr::{compiled::{LrCompiledTable, LrCompiledTableError}, LrParser}, traits::Parser};
+
+mod common;
+
+use common::{list::RULES, tokens};
+
+/// Generated by `LrCompiledTable::to_rust_source`, see [compiled_table_is_up_to_date].
+static TABLE: LrCompiledTable = include!("compiled/list_table.rs");
+
+#[test]
+fn compiled_table_is_up_to_date() {
+    let compiled = LrParser::generate(&RULES).unwrap().compile();
+    assert_eq!(compiled, TABLE, "generated table:\n{}", compiled.to_rust_source());
+    assert_eq!(compiled.to_rust_source(), include_str!("compiled/list_table.rs").trim_end());
+}
+
+#[test]
+fn load_compiled_table() {
+    let parser = LrParser::load(&RULES, &TABLE).unwrap();
+    let list: Vec<u32> = parser.parse(tokens(&[1, 2, 3])).unwrap();
+    assert_eq!(list, vec![1, 2, 3]);
+}
+
+#[test]
+fn reject_table_of_another_grammar() {
+    let mut compiled = TABLE.clone();
+    compiled.fingerprint += 1;
+
+    assert!(matches!(
+        LrParser::load(&RULES, &compiled),
+        Err(LrCompiledTableError::FingerprintMismatch { .. })
+    ));
+}
@@ -0,0 +1,283 @@
+use lazy_static::lazy_static;
+use yalp::lexer::{rule::LexerRuleSet, Lexer};
+use yalp::parser::{fuzz::{fuzz, SentenceGenerator}, lr::LrParser, traits::Parser, ParserError, ParserErrorKind, UnexpectedToken};
+use yalp::source::SourceMap;
+use yalp::symbol::Sym;
+
+mod arith {
+    yalp::grammar! {
+        pub symbols ArithDef { class: Class, value: Value }
+        pub rules RULES;
+
+        terminals {
+            /// End of stream
+            EOS = "end of input",
+            Plus = "`+`",
+            Minus = "`-`",
+            Mult = "`*`",
+            Number: i32 = "number",
+            /// Unary minus, only used for its precedence
+            UMinus,
+            Error
+        }
+
+        nonterminals {
+            Root: i32,
+            Expr: i32
+        }
+
+        eos EOS;
+        error Error;
+
+        left Plus Minus;
+        left Mult;
+        right UMinus;
+
+        Root -> Expr => |e| Ok(e);
+        Expr -> Expr Plus Expr => |lhs, _, rhs| Ok(lhs + rhs);
+        Expr -> Expr Minus Expr => |lhs, _, rhs| Ok(lhs - rhs);
+        Expr -> Expr Mult Expr => |lhs, _, rhs| Ok(lhs * rhs);
+        Expr -> Minus Expr %prec UMinus => |_, e| Ok(-e);
+        Expr -> Number => |n| Ok(n);
+        Expr -> Error => |_| Ok(0);
+    }
+}
+
+use arith::{ArithDef, Class, Value, RULES};
+
+lazy_static! {
+    static ref LEXER_RULES: LexerRuleSet<ArithDef> = LexerRuleSet::new()
+        .eos(&|_| Value::EOS)
+        .skip(r"\s+")
+        .literal(Class::Plus, "+", &|_| Value::Plus)
+        .literal(Class::Minus, "-", &|_| Value::Minus)
+        .literal(Class::Mult, "*", &|_| Value::Mult)
+        .token(Class::Number, r"\d+", &|s| Value::Number(s.parse().unwrap()))
+        .to_owned();
+}
+
+fn tokens(classes: Vec<(Class, Value)>) -> impl Iterator<Item=Result<Sym<ArithDef>, ParserError>> {
+    classes
+    .into_iter()
+    .chain([(Class::EOS, Value::EOS)])
+    .enumerate()
+    .map(|(i, (class, value))| Ok(Sym::new(i, class, value)))
+}
+
+fn number(n: i32) -> (Class, Value) {
+    (Class::Number, Value::Number(n))
+}
+
+#[test]
+fn grammar_generates_a_valid_parser() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    // - 2 + 3 * 4 - 1
+    let result: i32 = parser.parse(tokens(vec![
+        (Class::Minus, Value::Minus), number(2),
+        (Class::Plus, Value::Plus), number(3),
+        (Class::Mult, Value::Mult), number(4),
+        (Class::Minus, Value::Minus), number(1)
+    ]))
+    .unwrap();
+
+    assert_eq!(result, 9);
+}
+
+#[test]
+fn grammar_reports_syntax_errors() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    // 1 + * 2
+    let errors = parser.parse::<i32, _, _, _>(tokens(vec![
+        number(1), (Class::Plus, Value::Plus), (Class::Mult, Value::Mult), number(2)
+    ]))
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "expected `-` or number, found `*`");
+    assert_eq!(errors[0].kind, ParserErrorKind::UnexpectedToken(Box::new(UnexpectedToken {
+        found: "`*`".to_string(),
+        expected: vec!["`-`".to_string(), "number".to_string()],
+        found_class: "Mult".to_string(),
+        expected_classes: vec!["Minus".to_string(), "Number".to_string()]
+    })));
+}
+
+#[test]
+fn grammar_reports_truncated_input() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let truncated = |classes: Vec<(Class, Value)>| classes
+        .into_iter()
+        .enumerate()
+        .map(|(i, (class, value))| Ok::<_, ParserError>(Sym::new(i, class, value)));
+
+    // 1 +
+    let errors = parser.parse::<i32, _, _, _>(truncated(vec![number(1), (Class::Plus, Value::Plus)])).unwrap_err();
+    assert_eq!(errors.last().unwrap().kind, ParserErrorKind::IncompleteInput);
+
+    // 1 + 2 2, the stream ends while discarding the tokens following the error.
+    let errors = parser.parse::<i32, _, _, _>(truncated(vec![number(1), (Class::Plus, Value::Plus), number(2), number(2)])).unwrap_err();
+    assert_eq!(errors.last().unwrap().kind, ParserErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn grammar_parses_plain_text() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let lexer = Lexer::generate(&LEXER_RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    let result: i32 = parser.parse(lexer.tokenize("- 2 + 3 * 4\n - 1")).unwrap();
+    assert_eq!(result, 9);
+}
+
+#[test]
+fn grammar_renders_errors_in_source() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let lexer = Lexer::generate(&LEXER_RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    let mut sources = SourceMap::new();
+    sources.add("first.txt", "1 + 1");
+    let file = sources.add("second.txt", "1 +\n * 2");
+
+    let errors = parser.parse::<i32, _, _, _>(lexer.tokenize_file(file)).unwrap_err();
+    let rendered = errors[0].render(&sources);
+
+    assert!(rendered.contains("--> second.txt:2:2"), "{}", rendered);
+    assert!(rendered.ends_with("2 |  * 2\n  |  ^"), "{}", rendered);
+}
+
+mod list {
+    yalp::grammar! {
+        pub symbols ListDef { class: Class, value: Value }
+        pub rules RULES;
+
+        terminals {
+            EOS,
+            LeftBracket,
+            RightBracket,
+            Comma,
+            Item: u32
+        }
+
+        nonterminals {
+            Root: Vec<Vec<u32>>,
+            List: Vec<u32>
+        }
+
+        eos EOS;
+
+        Root -> List* => |lists| Ok(lists);
+        List -> LeftBracket (Item (Comma Item)* Comma?)? RightBracket => |_, items, _| {
+            Ok(match items {
+                Some((first, rest, _)) => std::iter::once(first).chain(rest.into_iter().map(|(_, item)| item)).collect(),
+                None => vec![]
+            })
+        };
+    }
+}
+
+#[test]
+fn grammar_desugars_ebnf_operators() {
+    use list::{Class, Value};
+
+    let parser = LrParser::generate(&list::RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    // [] [1] [1, 2, 3,]
+    let tokens = vec![
+        (Class::LeftBracket, Value::LeftBracket), (Class::RightBracket, Value::RightBracket),
+        (Class::LeftBracket, Value::LeftBracket), (Class::Item, Value::Item(1)), (Class::RightBracket, Value::RightBracket),
+        (Class::LeftBracket, Value::LeftBracket),
+        (Class::Item, Value::Item(1)), (Class::Comma, Value::Comma),
+        (Class::Item, Value::Item(2)), (Class::Comma, Value::Comma),
+        (Class::Item, Value::Item(3)), (Class::Comma, Value::Comma),
+        (Class::RightBracket, Value::RightBracket),
+        (Class::EOS, Value::EOS)
+    ];
+
+    let lists: Vec<Vec<u32>> = parser
+        .parse(tokens.into_iter().enumerate().map(|(i, (class, value))| Ok::<_, ParserError>(Sym::<list::ListDef>::new(i, class, value))))
+        .unwrap();
+
+    assert_eq!(lists, vec![vec![], vec![1], vec![1, 2, 3]]);
+}
+
+mod counted {
+    yalp::grammar! {
+        pub symbols CountedDef { class: Class, value: Value }
+        pub rules RULES;
+
+        /// The number of items
+        context usize;
+
+        terminals {
+            EOS,
+            Item: u32
+        }
+
+        nonterminals {
+            Root: u32,
+            Sum: u32
+        }
+
+        eos EOS;
+
+        Root -> Sum => |sum| Ok(sum);
+        Sum -> Sum Item => |sum, item, count| {
+            *count += 1;
+            Ok(sum + item)
+        };
+        Sum -> => || Ok(0);
+    }
+}
+
+#[test]
+fn grammar_passes_the_context() {
+    use counted::{Class, Value};
+
+    let parser = LrParser::generate(&counted::RULES).unwrap_or_else(|err| panic!("{}", err));
+    let tokens = vec![(Class::Item, Value::Item(1)), (Class::Item, Value::Item(2)), (Class::Item, Value::Item(3)), (Class::EOS, Value::EOS)];
+
+    let mut count = 0;
+    let sum: u32 = parser
+        .parse_with_context(tokens.into_iter().enumerate().map(|(i, (class, value))| Ok::<_, ParserError>(Sym::<counted::CountedDef>::new(i, class, value))), &mut count)
+        .unwrap();
+
+    assert_eq!(sum, 6);
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn grammar_builds_a_lossless_tree() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let lexer = Lexer::generate(&LEXER_RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    let input = "  - 2 +\t3 * 4\n - 1 \n";
+    let cst = parser.parse_cst(lexer.tokenize_lossless(input)).unwrap();
+
+    assert_eq!(cst.to_source(), input);
+    assert_eq!(*cst.root.class(), Class::Root);
+    assert_eq!(cst.root.tokens().len(), 8);
+    assert_eq!(cst.root.tokens()[0].leading_trivia[0].text, "  ");
+    assert_eq!(cst.eos.leading_trivia[0].text, " \n");
+
+    let expr = &cst.root.children()[0];
+    assert_eq!(*expr.class(), Class::Expr);
+    assert_eq!(expr.children().iter().map(|child| child.class().clone()).collect::<Vec<_>>(), vec![Class::Expr, Class::Minus, Class::Expr]);
+}
+
+#[test]
+fn grammar_survives_random_sentences() {
+    let parser = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let mut generator = SentenceGenerator::new(&RULES, 7);
+    generator.max_depth(6);
+
+    let failures = fuzz(&parser, &mut generator, 1000, |class| match class {
+        Class::Plus => Value::Plus,
+        Class::Minus => Value::Minus,
+        Class::Mult => Value::Mult,
+        Class::Number => Value::Number(1),
+        _ => Value::EOS
+    });
+
+    assert!(failures.is_empty(), "{}", failures.iter().map(|failure| failure.to_string()).collect::<Vec<_>>().join("\n"));
+}
@@ -0,0 +1,40 @@
+use yalp::parser::{ll::LlParser, lr::LrParser, traits::Parser};
+use yalp::symbol::traits::Symbol;
+
+mod common;
+
+use common::{list::{ListDef, Class, RULES}, tokens};
+
+/// Generated by `LlParser::to_rust_source`, see [descent_is_up_to_date].
+#[allow(dead_code)]
+mod descent {
+    include!("compiled/list_descent.rs");
+}
+
+#[test]
+fn descent_is_up_to_date() {
+    let parser = LlParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let src = parser.to_rust_source("super::ListDef", "super::Class");
+    assert_eq!(src, include_str!("compiled/list_descent.rs"), "generated functions:\n{}", src);
+}
+
+#[test]
+fn descent_parses_as_the_lr_parser() {
+    let ll = LlParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+    let lr = LrParser::generate(&RULES).unwrap_or_else(|err| panic!("{}", err));
+
+    for items in [vec![1], vec![1, 2, 3]] {
+        let expected: Vec<u32> = lr.parse(tokens(&items)).unwrap();
+        assert_eq!(expected, items);
+
+        let table: Vec<u32> = ll.parse(tokens(&items)).unwrap();
+        assert_eq!(table, expected);
+
+        let descent: Vec<u32> = descent::parse(&RULES, tokens(&items), &mut ()).and_then(|sym| sym.into_value()).unwrap();
+        assert_eq!(descent, expected);
+    }
+
+    let error = descent::parse(&RULES, tokens(&[]), &mut ()).unwrap_err();
+    let errors = ll.parse::<Vec<u32>, _, _, _>(tokens(&[])).unwrap_err();
+    assert_eq!(error.message, errors[0].message);
+}